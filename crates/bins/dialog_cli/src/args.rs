@@ -0,0 +1,316 @@
+use std::fmt::Display;
+
+use crate::format::OutputFormat;
+
+pub(crate) const USAGE: &str = "\
+Usage: dialog-cli [options] <box-option>
+
+Box options:
+  --msgbox <text> <height> <width>
+  --yesno <text> <height> <width>
+  --inputbox <text> <height> <width> [init]
+  --passwordbox <text> <height> <width> [init]
+  --menu <text> <height> <width> <list-height> [tag item] ...
+  --checklist <text> <height> <width> <list-height> [tag item status] ...
+  --radiolist <text> <height> <width> <list-height> [tag item status] ...
+  --buildlist <text> <height> <width> <list-height> [tag item status] ...
+  --gauge <text> <height> <width> [percent]
+  --textbox <file> <height> <width>
+  --tailbox <file> <height> <width>
+  --fselect <path> <height> <width>
+  --dselect <path> <height> <width>
+  --spec <file>                 JSON or TOML dialog spec, `-` reads JSON from stdin
+
+Options:
+  --title <title>
+  --ok-button <text>
+  --cancel-button <text>
+  --yes-button <text>
+  --no-button <text>
+  --nocancel
+  --defaultno
+  --separate-output
+  --output-fd <fd>
+  --json                        Same as --format json
+  --format <format>             plain (default), json (default for --spec), shell, env or tsv";
+
+#[derive(Debug)]
+pub(crate) struct ArgsError(pub(crate) String);
+
+impl Display for ArgsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum BoxKind {
+    MsgBox,
+    YesNo,
+    InputBox { init: String },
+    PasswordBox { init: String },
+    Menu { list_height: usize, items: Vec<(String, String)> },
+    CheckList { list_height: usize, items: Vec<(String, String, bool)> },
+    RadioList { list_height: usize, items: Vec<(String, String, bool)> },
+    BuildList { list_height: usize, items: Vec<(String, String, bool)> },
+    Gauge { percent: usize },
+    TextBox,
+    TailBox,
+    FileSelect { directory: bool },
+    Spec { path: String }
+}
+
+#[derive(Debug)]
+pub(crate) struct Args {
+    pub(crate) kind: BoxKind,
+    pub(crate) text: String,
+    pub(crate) height: usize,
+    pub(crate) width: usize,
+
+    pub(crate) title: String,
+    pub(crate) ok_label: String,
+    pub(crate) cancel_label: String,
+    pub(crate) yes_label: String,
+    pub(crate) no_label: String,
+    pub(crate) no_cancel: bool,
+    pub(crate) default_no: bool,
+    pub(crate) separate_output: bool,
+    pub(crate) output_fd: Option<i32>,
+    pub(crate) format: OutputFormat
+}
+
+impl Args {
+    pub(crate) fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, ArgsError> {
+        let mut args = args.into_iter().peekable();
+        let mut kind = None;
+        let mut text = String::new();
+        let mut height = 0;
+        let mut width = 0;
+        let mut format = None;
+
+        let mut parsed = Self {
+            kind: BoxKind::MsgBox,
+            text: String::new(),
+            height: 0,
+            width: 0,
+            title: String::new(),
+            ok_label: "Ok".into(),
+            cancel_label: "Cancel".into(),
+            yes_label: "Yes".into(),
+            no_label: "No".into(),
+            no_cancel: false,
+            default_no: false,
+            separate_output: false,
+            output_fd: None,
+            format: OutputFormat::Plain
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--title" => parsed.title = value(&mut args, &arg)?,
+                "--ok-button" => parsed.ok_label = value(&mut args, &arg)?,
+                "--cancel-button" => parsed.cancel_label = value(&mut args, &arg)?,
+                "--yes-button" => parsed.yes_label = value(&mut args, &arg)?,
+                "--no-button" => parsed.no_label = value(&mut args, &arg)?,
+                "--nocancel" => parsed.no_cancel = true,
+                "--defaultno" => parsed.default_no = true,
+                "--separate-output" => parsed.separate_output = true,
+                "--output-fd" => parsed.output_fd = Some(match number(&value(&mut args, &arg)?, &arg)? {
+                    fd if fd < 0 => return Err(ArgsError(format!("{} expects a file descriptor, found {}", arg, fd))),
+                    fd => fd
+                }),
+                "--json" => format = Some(OutputFormat::Json),
+                "--format" => format = Some(value(&mut args, &arg)?.parse().map_err(ArgsError)?),
+                "--spec" => {
+                    if kind.is_some() {
+                        return Err(ArgsError("only one box option can be given".into()));
+                    }
+                    kind = Some(BoxKind::Spec { path: value(&mut args, &arg)? });
+                },
+                "--msgbox" | "--yesno" | "--inputbox" | "--passwordbox" | "--menu" | "--checklist" | "--radiolist" | "--buildlist" | "--gauge" | "--textbox" | "--tailbox" | "--fselect" | "--dselect" => {
+                    if kind.is_some() {
+                        return Err(ArgsError("only one box option can be given".into()));
+                    }
+
+                    text = value(&mut args, &arg)?.replace("\\n", "\n");
+                    height = number(&value(&mut args, &arg)?, &arg)?;
+                    width = number(&value(&mut args, &arg)?, &arg)?;
+
+                    kind = Some(match arg.as_str() {
+                        "--msgbox" => BoxKind::MsgBox,
+                        "--yesno" => BoxKind::YesNo,
+                        "--inputbox" => BoxKind::InputBox { init: optional(&mut args) },
+                        "--passwordbox" => BoxKind::PasswordBox { init: optional(&mut args) },
+                        "--textbox" => BoxKind::TextBox,
+                        "--tailbox" => BoxKind::TailBox,
+                        "--fselect" => BoxKind::FileSelect { directory: false },
+                        "--dselect" => BoxKind::FileSelect { directory: true },
+                        "--gauge" => match optional(&mut args) {
+                            percent if percent.is_empty() => BoxKind::Gauge { percent: 0 },
+                            percent => BoxKind::Gauge { percent: number(&percent, &arg)? }
+                        },
+                        "--menu" => {
+                            let list_height = number(&value(&mut args, &arg)?, &arg)?;
+                            let items = items(&mut args, 2, &format!("{} items need a tag and a description", arg))?
+                                .into_iter()
+                                .map(|[tag, description, ..]| (tag, description))
+                                .collect();
+                            BoxKind::Menu { list_height, items }
+                        },
+                        _ => {
+                            let list_height = number(&value(&mut args, &arg)?, &arg)?;
+                            let items = items(&mut args, 3, &format!("{} items need a tag, a description and a status", arg))?
+                                .into_iter()
+                                .map(|[tag, description, status]| (tag, description, is_on(&status)))
+                                .collect();
+                            match arg.as_str() {
+                                "--checklist" => BoxKind::CheckList { list_height, items },
+                                "--buildlist" => BoxKind::BuildList { list_height, items },
+                                _ => BoxKind::RadioList { list_height, items }
+                            }
+                        }
+                    });
+                },
+                other => return Err(ArgsError(format!("unknown option {:?}", other)))
+            }
+        }
+
+        parsed.kind = kind.ok_or_else(|| ArgsError("no box option given".into()))?;
+        parsed.format = match (format, &parsed.kind) {
+            (Some(format), _) => format,
+            (None, BoxKind::Spec { .. }) => OutputFormat::Json,
+            (None, _) => OutputFormat::Plain
+        };
+        parsed.text = text;
+        parsed.height = height;
+        parsed.width = width;
+
+        Ok(parsed)
+    }
+}
+
+fn value(args: &mut impl Iterator<Item = String>, option: &str) -> Result<String, ArgsError> {
+    args.next().ok_or_else(|| ArgsError(format!("{} is missing an argument", option)))
+}
+
+fn number<T: std::str::FromStr>(value: &str, option: &str) -> Result<T, ArgsError> {
+    value.parse().map_err(|_| ArgsError(format!("{} expects a number, found {:?}", option, value)))
+}
+
+// List items of `arity` values each, up to the next option. Only a tag can end the list, so
+// descriptions and statuses are taken as they are even when they start with "--". Menu items
+// leave the status empty.
+fn items(args: &mut std::iter::Peekable<impl Iterator<Item = String>>, arity: usize, error: &str) -> Result<Vec<[String; 3]>, ArgsError> {
+    let mut items = Vec::new();
+    while let Some(tag) = args.next_if(|a| !a.starts_with("--")) {
+        let mut item = [tag, String::new(), String::new()];
+        for value in item.iter_mut().take(arity).skip(1) {
+            *value = args.next().ok_or_else(|| ArgsError(error.into()))?;
+        }
+        items.push(item);
+    }
+    Ok(items)
+}
+
+fn optional(args: &mut std::iter::Peekable<impl Iterator<Item = String>>) -> String {
+    args.next_if(|a| !a.starts_with("--")).unwrap_or_default()
+}
+
+fn is_on(status: &str) -> bool {
+    matches!(status.to_lowercase().as_str(), "on" | "1" | "yes" | "true")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Case = (&'static [&'static str], fn(&BoxKind) -> bool);
+
+    fn parse(args: &[&str]) -> Result<Args, ArgsError> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn box_options_take_text_height_and_width() {
+        let cases: &[Case] = &[
+            (&["--msgbox", "Hello", "8", "40"], |k| matches!(k, BoxKind::MsgBox)),
+            (&["--yesno", "Hello", "8", "40"], |k| matches!(k, BoxKind::YesNo)),
+            (&["--inputbox", "Hello", "8", "40"], |k| matches!(k, BoxKind::InputBox { init } if init.is_empty())),
+            (&["--inputbox", "Hello", "8", "40", "me"], |k| matches!(k, BoxKind::InputBox { init } if init == "me")),
+            (&["--passwordbox", "Hello", "8", "40", "secret"], |k| matches!(k, BoxKind::PasswordBox { init } if init == "secret")),
+            (&["--gauge", "Hello", "8", "40"], |k| matches!(k, BoxKind::Gauge { percent: 0 })),
+            (&["--gauge", "Hello", "8", "40", "25"], |k| matches!(k, BoxKind::Gauge { percent: 25 })),
+            (&["--textbox", "Hello", "8", "40"], |k| matches!(k, BoxKind::TextBox)),
+            (&["--tailbox", "Hello", "8", "40"], |k| matches!(k, BoxKind::TailBox)),
+            (&["--fselect", "Hello", "8", "40"], |k| matches!(k, BoxKind::FileSelect { directory: false })),
+            (&["--dselect", "Hello", "8", "40"], |k| matches!(k, BoxKind::FileSelect { directory: true }))
+        ];
+
+        for (args, expected) in cases {
+            let parsed = parse(args).unwrap();
+            assert!(expected(&parsed.kind), "{:?} parsed as {:?}", args, parsed.kind);
+            assert_eq!((parsed.text.as_str(), parsed.height, parsed.width), ("Hello", 8, 40), "{:?}", args);
+            assert_eq!(parsed.format, OutputFormat::Plain);
+        }
+    }
+
+    #[test]
+    fn list_options_take_items() {
+        let parsed = parse(&["--menu", "Pick", "0", "0", "5", "a", "Apple", "b", "--force mode"]).unwrap();
+        assert!(matches!(parsed.kind, BoxKind::Menu { list_height: 5, items } if items == [
+            ("a".to_string(), "Apple".to_string()),
+            ("b".to_string(), "--force mode".to_string())
+        ]));
+
+        for option in ["--checklist", "--radiolist", "--buildlist"] {
+            let parsed = parse(&[option, "Pick", "0", "0", "3", "a", "Apple", "on", "b", "Banana", "off", "--title", "Fruit"]).unwrap();
+            let items = match parsed.kind {
+                BoxKind::CheckList { list_height: 3, items } | BoxKind::RadioList { list_height: 3, items } | BoxKind::BuildList { list_height: 3, items } => items,
+                kind => panic!("{} parsed as {:?}", option, kind)
+            };
+            assert_eq!(items, [("a".to_string(), "Apple".to_string(), true), ("b".to_string(), "Banana".to_string(), false)]);
+            assert_eq!(parsed.title, "Fruit");
+        }
+    }
+
+    #[test]
+    fn incomplete_items_are_rejected() {
+        assert!(parse(&["--menu", "Pick", "0", "0", "5", "a"]).is_err());
+        assert!(parse(&["--checklist", "Pick", "0", "0", "5", "a", "Apple"]).is_err());
+    }
+
+    #[test]
+    fn height_and_width_must_be_numbers() {
+        assert!(parse(&["--msgbox", "Hello", "tall", "40"]).is_err());
+        assert!(parse(&["--msgbox", "Hello", "8", "-1"]).is_err());
+        assert!(parse(&["--msgbox", "Hello", "8"]).is_err());
+    }
+
+    #[test]
+    fn output_fd_must_be_a_descriptor() {
+        assert_eq!(parse(&["--output-fd", "3", "--msgbox", "Hello", "0", "0"]).unwrap().output_fd, Some(3));
+        assert_eq!(parse(&["--msgbox", "Hello", "0", "0"]).unwrap().output_fd, None);
+        assert!(parse(&["--output-fd", "-1", "--msgbox", "Hello", "0", "0"]).is_err());
+        assert!(parse(&["--output-fd", "stdout", "--msgbox", "Hello", "0", "0"]).is_err());
+    }
+
+    #[test]
+    fn options_and_formats() {
+        let parsed = parse(&["--title", "T", "--ok-button", "Go", "--nocancel", "--defaultno", "--separate-output", "--format", "shell", "--yesno", "Sure?\\nReally?", "0", "0"]).unwrap();
+        assert_eq!((parsed.title.as_str(), parsed.ok_label.as_str()), ("T", "Go"));
+        assert!(parsed.no_cancel && parsed.default_no && parsed.separate_output);
+        assert_eq!(parsed.format, OutputFormat::Shell);
+        assert_eq!(parsed.text, "Sure?\nReally?");
+
+        assert_eq!(parse(&["--json", "--msgbox", "Hi", "0", "0"]).unwrap().format, OutputFormat::Json);
+        assert_eq!(parse(&["--spec", "form.toml"]).unwrap().format, OutputFormat::Json);
+        assert!(parse(&["--format", "xml", "--msgbox", "Hi", "0", "0"]).is_err());
+    }
+
+    #[test]
+    fn exactly_one_box_option() {
+        assert!(parse(&["--title", "T"]).is_err());
+        assert!(parse(&["--msgbox", "Hi", "0", "0", "--yesno", "Hi", "0", "0"]).is_err());
+        assert!(parse(&["--bogus"]).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use std::str::FromStr;
+
+use dialog::dialog::{DialogResult, FormData};
+use serde_json::{json, Map, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum OutputFormat {
+    /// whiptail compatible: the bare value of the single input.
+    Plain,
+    Json,
+    /// `NAME='value'` lines for `eval`.
+    Shell,
+    /// `NAME=value` lines in .env style.
+    Env,
+    /// `name<TAB>value` lines.
+    Tsv
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(Self::Plain),
+            "json" => Ok(Self::Json),
+            "shell" => Ok(Self::Shell),
+            "env" => Ok(Self::Env),
+            "tsv" => Ok(Self::Tsv),
+            _ => Err(format!("unknown format {:?}, expected plain, json, shell, env or tsv", s))
+        }
+    }
+}
+
+pub(crate) fn json(result: &DialogResult, data: &FormData) -> String {
+    let values = data
+        .iter()
+        .map(|(name, value)| (name.to_string(), Value::String(value.to_string())))
+        .collect::<Map<_, _>>();
+
+    format!("{}\n", json!({ "result": result.to_string(), "values": values }))
+}
+
+pub(crate) fn shell(data: &FormData) -> String {
+    data.iter()
+        .map(|(name, value)| format!("{}={}\n", variable_name(name), shell_quote(value)))
+        .collect()
+}
+
+pub(crate) fn env(data: &FormData) -> String {
+    data.iter()
+        .map(|(name, value)| format!("{}={}\n", variable_name(name), env_quote(value)))
+        .collect()
+}
+
+pub(crate) fn tsv(data: &FormData) -> String {
+    data.iter()
+        .map(|(name, value)| format!("{}\t{}\n", tsv_escape(name), tsv_escape(value)))
+        .collect()
+}
+
+/// Turns a control name into a shell variable name, e.g. "First Name" -> "FIRST_NAME".
+fn variable_name(name: &str) -> String {
+    let name = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.to_ascii_uppercase())
+        .collect::<Vec<_>>()
+        .join("_");
+
+    match name.chars().next() {
+        Some(c) if !c.is_ascii_digit() => name,
+        _ => format!("_{}", name)
+    }
+}
+
+// Inside single quotes nothing is special except the quote itself, which has to be closed, escaped and reopened.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn env_quote(value: &str) -> String {
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || "_-.,/:@+".contains(c)) {
+        return value.to_string();
+    }
+
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' | '\\' | '$' | '`' => { quoted.push('\\'); quoted.push(c); },
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c)
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn tsv_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(values: &[(&str, &str)]) -> FormData {
+        values.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn variable_names_are_upper_snake_case() {
+        assert_eq!(variable_name("First Name"), "FIRST_NAME");
+        assert_eq!(variable_name("e-mail  address"), "E_MAIL_ADDRESS");
+        assert_eq!(variable_name("2fa"), "_2FA");
+        assert_eq!(variable_name(""), "_");
+    }
+
+    #[test]
+    fn shell_quotes_everything_and_escapes_single_quotes() {
+        assert_eq!(shell(&data(&[("Name", "it's $HOME")])), "NAME='it'\\''s $HOME'\n");
+        assert_eq!(shell(&data(&[("Empty", "")])), "EMPTY=''\n");
+    }
+
+    #[test]
+    fn env_only_quotes_when_needed() {
+        assert_eq!(env(&data(&[("Path", "/usr/local/bin")])), "PATH=/usr/local/bin\n");
+        assert_eq!(env(&data(&[("Empty", "")])), "EMPTY=\"\"\n");
+        assert_eq!(env(&data(&[("Cmd", "say \"hi\" `id` $USER\\\nbye")])), "CMD=\"say \\\"hi\\\" \\`id\\` \\$USER\\\\\\nbye\"\n");
+    }
+
+    #[test]
+    fn tsv_escapes_tabs_newlines_and_backslashes() {
+        assert_eq!(tsv(&data(&[("a\tb", "one\ntwo\\")])), "a\\tb\tone\\ntwo\\\\\n");
+    }
+
+    #[test]
+    fn json_round_trips_values() {
+        let output = json(&DialogResult::Ok, &data(&[("Name", "\"quoted\"\n")]));
+        let value = serde_json::from_str::<Value>(&output).unwrap();
+
+        assert_eq!(value["values"]["Name"], "\"quoted\"\n");
+        assert_eq!(value["result"], DialogResult::Ok.to_string());
+    }
+}
@@ -0,0 +1,135 @@
+use std::io::{stdin, BufRead};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+use crossterm::event::{poll, read, Event, KeyCode, KeyEventKind, KeyModifiers};
+
+use dialog::dialog::{Dialog, DialogResult};
+
+pub(crate) const GAUGE_NAME: &str = "gauge";
+pub(crate) const TEXT_NAME: &str = "text";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, PartialEq)]
+enum Update {
+    Percent(usize),
+    Message(String)
+}
+
+/// Parses the dialog(1) gauge protocol: a line holding a number sets the percentage, and a block between two
+/// `XXX` lines replaces the message, optionally starting with a new percentage.
+#[derive(Debug, Default)]
+struct Parser {
+    message: Option<Vec<String>>
+}
+
+impl Parser {
+    fn parse(&mut self, line: &str) -> Option<Update> {
+        let line = line.trim_end_matches('\r');
+
+        match (&mut self.message, line.trim()) {
+            (None, "XXX") => {
+                self.message = Some(Vec::new());
+                None
+            },
+            (None, line) => line.parse().ok().map(Update::Percent),
+            (Some(lines), "XXX") => {
+                let message = lines.join("\n").replace("\\n", "\n");
+                self.message = None;
+                Some(Update::Message(message))
+            },
+            (Some(lines), trimmed) => match trimmed.parse() {
+                Ok(percent) if lines.is_empty() => Some(Update::Percent(percent)),
+                _ => {
+                    lines.push(line.to_string());
+                    None
+                }
+            }
+        }
+    }
+}
+
+// Stdin is read on its own thread so the dialog can keep handling resizes while a script is quiet.
+fn spawn_reader() -> Receiver<Update> {
+    let (sender, receiver) = channel();
+
+    thread::spawn(move || {
+        let mut parser = Parser::default();
+        for line in stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if let Some(update) = parser.parse(&line) {
+                if sender.send(update).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    receiver
+}
+
+/// Shows progress until stdin is closed.
+pub(crate) fn event_loop(dialog: &mut Dialog) -> dialog::Result<DialogResult> {
+    dialog.show()?;
+    let updates = spawn_reader();
+
+    loop {
+        loop {
+            match updates.try_recv() {
+                Ok(Update::Percent(percent)) => dialog.set_value(GAUGE_NAME, &percent.to_string())?,
+                Ok(Update::Message(message)) => dialog.set_value(TEXT_NAME, &message)?,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return Ok(DialogResult::Ok)
+            }
+        }
+
+        if poll(POLL_INTERVAL)? {
+            match read()? {
+                Event::Key(event) if event.kind == KeyEventKind::Press && event.code == KeyCode::Char('c') && event.modifiers == KeyModifiers::CONTROL => {
+                    return Ok(DialogResult::Abort);
+                },
+                Event::Resize(_, _) => dialog.show()?,
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_all(lines: &[&str]) -> Vec<Update> {
+        let mut parser = Parser::default();
+        lines.iter().filter_map(|line| parser.parse(line)).collect()
+    }
+
+    #[test]
+    fn numbers_set_the_percentage() {
+        assert_eq!(parse_all(&["10", " 50 ", "100\r"]), [Update::Percent(10), Update::Percent(50), Update::Percent(100)]);
+    }
+
+    #[test]
+    fn junk_lines_are_ignored() {
+        assert_eq!(parse_all(&["", "abc", "-5", "12.5", "40"]), [Update::Percent(40)]);
+    }
+
+    #[test]
+    fn blocks_replace_the_message() {
+        let updates = parse_all(&["XXX", "75", "first line", "second\\nthird", "XXX"]);
+        assert_eq!(updates, [Update::Percent(75), Update::Message("first line\nsecond\nthird".to_string())]);
+    }
+
+    #[test]
+    fn numbers_inside_a_message_are_text() {
+        let updates = parse_all(&["XXX", "copying", "42", "XXX", "60"]);
+        assert_eq!(updates, [Update::Message("copying\n42".to_string()), Update::Percent(60)]);
+    }
+
+    #[test]
+    fn carriage_returns_are_trimmed() {
+        assert_eq!(parse_all(&["XXX\r", "done\r", "XXX\r"]), [Update::Message("done".to_string())]);
+    }
+}
@@ -0,0 +1,313 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{stderr, stdin, stdout, Read, Write};
+use std::mem::ManuallyDrop;
+use std::os::fd::FromRawFd;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use crossterm::cursor::{SetCursorStyle, Show};
+use crossterm::event::{poll, read, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, Colors};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, size, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::QueueableCommand;
+
+use dialog::colors::{DialogColors, GaugeColors, ListColors, TabColors, TextBoxColors};
+use dialog::controls::button::Button;
+use dialog::controls::buildlist::BuildList;
+use dialog::controls::field::Field;
+use dialog::controls::file_browser::{FileBrowser, FileMode};
+use dialog::controls::gauge::Gauge;
+use dialog::controls::label::Label;
+use dialog::controls::list::{List, ListItem, ListMode};
+use dialog::controls::tail::TailSource;
+use dialog::controls::textbox::TextBox;
+use dialog::controls::Control;
+use dialog::dialog::{Dialog, DialogBuilder, DialogResult, FormData};
+use dialog::spec::DialogSpec;
+
+mod args;
+mod format;
+mod gauge;
+mod tty;
+
+use args::{Args, BoxKind, USAGE};
+use format::OutputFormat;
+use tty::TtyRedirect;
+
+const DEFAULT_WIDTH: usize = 60;
+const DEFAULT_LIST_HEIGHT: usize = 10;
+const DEFAULT_TEXT_ROWS: usize = 20;
+const EXIT_LABEL: &str = "Exit";
+const UPDATE_INTERVAL: Duration = Duration::from_millis(200);
+const INPUT_NAME: &str = "input";
+const LIST_NAME: &str = "list";
+const TEXT_BOX_NAME: &str = "textbox";
+const PATH_NAME: &str = "path";
+
+// Exit codes as used by whiptail and dialog(1), which report errors as -1 like Esc.
+const EXIT_OK: u8 = 0;
+const EXIT_CANCEL: u8 = 1;
+const EXIT_ERROR: u8 = 255;
+const EXIT_ESC: u8 = 255;
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("dialog-cli: {}\n\n{}", e, USAGE);
+            return ExitCode::from(EXIT_ERROR);
+        }
+    };
+
+    match run(&args) {
+        Ok(code) => ExitCode::from(code),
+        Err(e) => {
+            eprintln!("dialog-cli: {}", e);
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+
+fn run(args: &Args) -> Result<u8, Box<dyn Error>> {
+    let mut dialog = match &args.kind {
+        BoxKind::Spec { path } => load_spec(path)?.into_builder()?.build()?,
+        _ => build_dialog(args)?
+    };
+
+    let redirect = TtyRedirect::new()?;
+    let result = match args.kind {
+        BoxKind::Gauge { .. } => show_dialog(&mut dialog, gauge::event_loop),
+        _ => show_dialog(&mut dialog, event_loop)
+    };
+    redirect.restore()?;
+    let result = result?;
+
+    let data = dialog.get_data();
+    match args.format {
+        // JSON carries the result itself, so it is written for cancelled dialogs too.
+        OutputFormat::Json => write_output(Some(args.output_fd.unwrap_or(1)), &format::json(&result, &data))?,
+        _ if !accepted(&result) => {},
+        OutputFormat::Plain => if let Some(output) = format_output(args, &data) {
+            write_output(args.output_fd, &output)?;
+        },
+        OutputFormat::Shell => write_output(Some(args.output_fd.unwrap_or(1)), &format::shell(&data))?,
+        OutputFormat::Env => write_output(Some(args.output_fd.unwrap_or(1)), &format::env(&data))?,
+        OutputFormat::Tsv => write_output(Some(args.output_fd.unwrap_or(1)), &format::tsv(&data))?
+    }
+
+    Ok(exit_code(&result))
+}
+
+fn load_spec(path: &str) -> Result<DialogSpec, Box<dyn Error>> {
+    if path == "-" {
+        let mut spec = String::new();
+        stdin().read_to_string(&mut spec)?;
+        return Ok(DialogSpec::from_json(&spec)?);
+    }
+
+    Ok(DialogSpec::from_file(path)?)
+}
+
+fn build_dialog(args: &Args) -> dialog::Result<Dialog> {
+    let inner_width = if args.width > 4 { args.width - 4 } else { DEFAULT_WIDTH };
+    let title = if args.title.is_empty() { String::new() } else { format!(" {} ", args.title) };
+
+    let builder = DialogBuilder::new(title)
+        .set_margin((1, 1).into())
+        .set_colors(colors())
+        .set_overlay(true)
+        .set_cancel_result(DialogResult::Abort)
+        .set_min_size(args.width, args.height);
+
+    let ok_cancel = |builder: DialogBuilder, first_tab: usize| {
+        let mut buttons = vec![(args.ok_label.as_str(), DialogResult::Ok)];
+        if !args.no_cancel {
+            buttons.push((args.cancel_label.as_str(), DialogResult::Cancel));
+        }
+        add_buttons(builder, &buttons, first_tab, false)
+    };
+
+    if let BoxKind::TextBox | BoxKind::TailBox = args.kind {
+        let rows = if args.height > 7 { args.height - 7 } else { terminal_rows(7, DEFAULT_TEXT_ROWS) };
+        let text_box = match args.kind {
+            BoxKind::TailBox => TextBox::new(TEXT_BOX_NAME, "", inner_width, rows, Some(0), 0).set_tail(TailSource::file(&args.text)),
+            _ => TextBox::new(TEXT_BOX_NAME, &String::from_utf8_lossy(&fs::read(&args.text)?), inner_width, rows, Some(0), 0)
+        };
+        return add_buttons(builder.add_control(Control::TextBox(text_box)), &[(EXIT_LABEL, DialogResult::Ok)], 1, false).build();
+    }
+
+    if let BoxKind::FileSelect { directory } = args.kind {
+        let rows = if args.height > 9 { args.height - 9 } else { terminal_rows(9, DEFAULT_LIST_HEIGHT) };
+        let mode = if directory { FileMode::Directory } else { FileMode::Open };
+        let browser = FileBrowser::new(PATH_NAME, &args.text, mode, inner_width, rows, Some(0), 0);
+        return ok_cancel(builder.add_control(Control::FileBrowser(browser)), 1).build();
+    }
+
+    let builder = builder.add_control(Control::Label(Label::new(&args.text, 0).set_wrap(inner_width).set_name(gauge::TEXT_NAME)));
+
+    match &args.kind {
+        BoxKind::MsgBox => add_buttons(builder, &[(args.ok_label.as_str(), DialogResult::Ok)], 0, false),
+        BoxKind::YesNo => add_buttons(builder, &[(args.yes_label.as_str(), DialogResult::Yes), (args.no_label.as_str(), DialogResult::No)], 0, args.default_no),
+        BoxKind::InputBox { init } | BoxKind::PasswordBox { init } => {
+            let field = Field::new(INPUT_NAME, inner_width.saturating_sub(2).max(1), 1024, Some(0), 1)
+                .set_label(None)
+                .set_masked(matches!(args.kind, BoxKind::PasswordBox { .. }))
+                .set_initial_value(init);
+            ok_cancel(builder.add_control(Control::TextField(field)), 1)
+        },
+        BoxKind::Menu { list_height, items } => {
+            let list = List::menu(LIST_NAME, items.iter().cloned(), list_rows(*list_height), Some(0), 1);
+            ok_cancel(builder.add_control(Control::List(list)), 1)
+        },
+        BoxKind::CheckList { list_height, items } | BoxKind::RadioList { list_height, items } => {
+            let mode = match args.kind {
+                BoxKind::CheckList { .. } => ListMode::Check,
+                _ => ListMode::Radio
+            };
+            let items = items.iter().map(|(tag, description, on)| ListItem::new(tag, description, *on)).collect::<Vec<_>>();
+            let list = List::new(LIST_NAME, items, list_rows(*list_height), mode, Some(0), 1);
+            ok_cancel(builder.add_control(Control::List(list)), 1)
+        },
+        BoxKind::BuildList { list_height, items } => {
+            let items = items.iter().map(|(tag, description, on)| ListItem::new(tag, description, *on)).collect::<Vec<_>>();
+            let build_list = BuildList::new(LIST_NAME, items, list_rows(*list_height), Some(0), 1);
+            ok_cancel(builder.add_control(Control::BuildList(build_list)), 1)
+        },
+        BoxKind::Gauge { percent } => {
+            builder.add_control(Control::Gauge(Gauge::new(gauge::GAUGE_NAME, inner_width, 1).set_percent(*percent)))
+        },
+        BoxKind::TextBox | BoxKind::TailBox | BoxKind::FileSelect { .. } => unreachable!("built without a label"),
+        BoxKind::Spec { .. } => unreachable!("specs are built by DialogSpec")
+    }.build()
+}
+
+fn add_buttons(builder: DialogBuilder, buttons: &[(&str, DialogResult)], first_tab: usize, focus_last: bool) -> DialogBuilder {
+    let count = buttons.len();
+
+    buttons.iter().enumerate().fold(builder, |builder, (i, (label, result))| {
+        let tab = if focus_last { first_tab + (i + 1) % count } else { first_tab + i };
+        builder.add_control(Control::Button(Button::new(*label, Some(tab), result.clone())))
+    })
+}
+
+// Rows that fill the terminal once `overhead` rows are taken by the rest of the dialog.
+fn terminal_rows(overhead: usize, fallback: usize) -> usize {
+    size().map(|(_, rows)| (rows as usize).saturating_sub(overhead).max(1)).unwrap_or(fallback)
+}
+
+fn list_rows(list_height: usize) -> usize {
+    if list_height == 0 { DEFAULT_LIST_HEIGHT } else { list_height }
+}
+
+fn colors() -> DialogColors {
+    DialogColors::new(
+        Colors::new(Color::Black, Color::Grey),
+        Colors::new(Color::Black, Color::Grey),
+        Colors::new(Color::White, Color::DarkBlue),
+        Colors::new(Color::Black, Color::Grey),
+        Colors::new(Color::White, Color::DarkBlue),
+        Colors::new(Color::Black, Color::Grey),
+        Colors::new(Color::Black, Color::Grey),
+        Colors::new(Color::DarkRed, Color::Grey)
+    ).set_lists(ListColors::new(
+        Colors::new(Color::Black, Color::Grey),
+        Colors::new(Color::White, Color::DarkRed)
+    )).set_gauges(GaugeColors::new(
+        Colors::new(Color::White, Color::DarkRed),
+        Colors::new(Color::Black, Color::Grey)
+    )).set_text_boxes(TextBoxColors::new(
+        Colors::new(Color::Black, Color::Grey),
+        Colors::new(Color::White, Color::DarkRed)
+    )).set_tabs(TabColors::new(
+        Colors::new(Color::Black, Color::Grey),
+        Colors::new(Color::White, Color::DarkRed)
+    ))
+}
+
+type EventLoop = fn(&mut Dialog) -> dialog::Result<DialogResult>;
+
+fn show_dialog(dialog: &mut Dialog, event_loop: EventLoop) -> dialog::Result<DialogResult> {
+    stdout()
+        .queue(EnterAlternateScreen)?
+        .flush()?;
+    enable_raw_mode()?;
+
+    let result = event_loop(dialog);
+
+    disable_raw_mode()?;
+    stdout()
+        .queue(LeaveAlternateScreen)?
+        .queue(SetCursorStyle::DefaultUserShape)?
+        .queue(Show)?
+        .flush()?;
+
+    result
+}
+
+fn event_loop(dialog: &mut Dialog) -> dialog::Result<DialogResult> {
+    dialog.show()?;
+
+    loop {
+        // Tail boxes pick up new lines whenever the user is idle.
+        if !poll(UPDATE_INTERVAL)? {
+            dialog.update()?;
+            continue;
+        }
+
+        match read()? {
+            Event::Key(event) if event.kind == KeyEventKind::Press => match (event.code, event.modifiers) {
+                (KeyCode::Char('c'), KeyModifiers::CONTROL) => return Ok(DialogResult::Abort),
+                (code, modifiers) => {
+                    let result = dialog.handle_input(code, modifiers)?;
+                    if result.should_quit {
+                        return Ok(result.dialog_result.unwrap_or(DialogResult::Abort));
+                    }
+                }
+            },
+            Event::Resize(_, _) => dialog.show()?,
+            _ => {}
+        }
+    }
+}
+
+fn accepted(result: &DialogResult) -> bool {
+    matches!(result, DialogResult::Ok | DialogResult::Yes)
+}
+
+fn exit_code(result: &DialogResult) -> u8 {
+    match result {
+        DialogResult::Ok | DialogResult::Yes => EXIT_OK,
+        DialogResult::Cancel | DialogResult::No => EXIT_CANCEL,
+        DialogResult::Abort => EXIT_ESC,
+        DialogResult::Retry | DialogResult::Ignore | DialogResult::Back | DialogResult::Next => EXIT_ERROR
+    }
+}
+
+fn format_output(args: &Args, data: &FormData) -> Option<String> {
+    match args.kind {
+        BoxKind::MsgBox | BoxKind::YesNo | BoxKind::Gauge { .. } | BoxKind::TextBox | BoxKind::TailBox | BoxKind::Spec { .. } => None,
+        BoxKind::InputBox { .. } | BoxKind::PasswordBox { .. } => data.get(INPUT_NAME).map(String::from),
+        BoxKind::FileSelect { .. } => data.get(PATH_NAME).map(String::from),
+        BoxKind::Menu { .. } | BoxKind::RadioList { .. } => data.get(LIST_NAME).map(String::from),
+        BoxKind::CheckList { .. } | BoxKind::BuildList { .. } => {
+            let tags = data.get_list(LIST_NAME);
+            Some(match args.separate_output {
+                true => tags.iter().map(|tag| format!("{}\n", tag)).collect(),
+                false => tags.iter().map(|tag| format!("\"{}\"", tag)).collect::<Vec<_>>().join(" ")
+            })
+        }
+    }
+}
+
+fn write_output(fd: Option<i32>, output: &str) -> Result<(), std::io::Error> {
+    match fd {
+        None | Some(2) => stderr().write_all(output.as_bytes()),
+        Some(1) => stdout().write_all(output.as_bytes()),
+        Some(fd) => {
+            // The descriptor belongs to the calling shell, so it must not be closed here.
+            let mut file = ManuallyDrop::new(unsafe { File::from_raw_fd(fd) });
+            file.write_all(output.as_bytes())
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::fs::OpenOptions;
+use std::io::{stdout, IsTerminal, Write};
+use std::os::fd::{AsFd, AsRawFd, OwnedFd};
+
+/// Points stdout at the terminal while the dialog is shown, so it can be drawn even when
+/// stdout is captured by the caller, e.g. `result=$(dialog-cli ...)`.
+pub(crate) struct TtyRedirect {
+    saved: Option<OwnedFd>
+}
+
+impl TtyRedirect {
+    pub(crate) fn new() -> Result<Self, std::io::Error> {
+        if stdout().is_terminal() {
+            return Ok(Self { saved: None });
+        }
+
+        let saved = stdout().as_fd().try_clone_to_owned()?;
+        let tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+        dup2(tty.as_raw_fd(), libc::STDOUT_FILENO)?;
+
+        Ok(Self { saved: Some(saved) })
+    }
+
+    /// Puts the original stdout back.
+    pub(crate) fn restore(mut self) -> Result<(), std::io::Error> {
+        stdout().flush()?;
+
+        match self.saved.take() {
+            Some(saved) => dup2(saved.as_raw_fd(), libc::STDOUT_FILENO),
+            None => Ok(())
+        }
+    }
+}
+
+impl Drop for TtyRedirect {
+    fn drop(&mut self) {
+        if let Some(saved) = self.saved.take() {
+            let _ = stdout().flush();
+            let _ = dup2(saved.as_raw_fd(), libc::STDOUT_FILENO);
+        }
+    }
+}
+
+fn dup2(from: i32, to: i32) -> Result<(), std::io::Error> {
+    match unsafe { libc::dup2(from, to) } {
+        -1 => Err(std::io::Error::last_os_error()),
+        _ => Ok(())
+    }
+}
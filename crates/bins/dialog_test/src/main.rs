@@ -8,15 +8,28 @@ use crossterm::QueueableCommand;
 use dialog::borders::{BorderStyle, Borders};
 use dialog::colors::DialogColors;
 use dialog::controls::button::Button;
-use dialog::controls::field::Field;
 use dialog::controls::Control;
-use dialog::dialog::{ButtonCount, DialogBuilder, DialogResult, DialogReturnValue};
+use dialog::dialog::{DialogResult, DialogReturnValue};
+use dialog::form::DialogForm;
+use dialog::history::History;
+use dialog::Dialog;
 use tracing::Level;
 use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
 
-fn main() -> Result<(), std::io::Error> {
+#[derive(Dialog)]
+#[dialog(title = " Add Contact ")]
+struct Contact {
+    #[dialog(history = "first_name")]
+    first_name: String,
+    last_name: String,
+    #[dialog(max = 40, completion = complete_company)]
+    company_name: String,
+    phone_number: String
+}
+
+fn main() -> dialog::Result<()> {
 
     // region:    -- Tracing Setup
 
@@ -44,7 +57,7 @@ fn main() -> Result<(), std::io::Error> {
         BorderStyle::Double
     );
 
-    let builder = DialogBuilder::new(" Add Contact ")
+    let builder = Contact::dialog_builder()
     .set_borders(borders)
     .set_margin((4, 1).into());
 
@@ -61,17 +74,14 @@ fn main() -> Result<(), std::io::Error> {
     
 
     let builder = builder
-        .add_control(Control::Button(Button::new("OK", Some(4), DialogResult::Ok, ButtonCount::One)))
-        .add_control(Control::Button(Button::new("Cancel", Some(5), DialogResult::Cancel, ButtonCount::Two)));
+        .add_control(Control::Button(Button::new("OK", Some(Contact::FIELD_COUNT), DialogResult::Ok)))
+        .add_control(Control::Button(Button::new("Cancel", Some(Contact::FIELD_COUNT + 1), DialogResult::Cancel)));
     
     let mut dialog = builder
-        .add_control(Control::TextField(Field::new("First Name", 15, 15, Some(0), 0)))
-        .add_control(Control::TextField(Field::new("Last Name", 15, 15, Some(1), 1)))
-        .add_control(Control::TextField(Field::new("Company Name", 15, 40, Some(2), 2)))
-        .add_control(Control::TextField(Field::new("Phone Number", 15, 15, Some(3), 3)))
         .set_colors(colors)
         .set_overlay(true)
-        .build();   
+        .set_history(History::load(std::env::temp_dir().join("dialog_test_history"), 20)?)
+        .build()?;
 
     // endregion: -- Setup Dialog
 
@@ -104,13 +114,25 @@ fn main() -> Result<(), std::io::Error> {
     }
 
     let data = dialog.get_data();
+    let contact = Contact::from_form_data(&data);
 
     // region:    -- End RawMode
     disable_raw_mode()?;
     stdout().queue(LeaveAlternateScreen)?.flush()?;
     // endregion: -- End RawMode
     
-    println!("Exited with: {:?}", data);
+    println!("Exited with: {:?}", result.dialog_result);
+    data.iter().for_each(|(name, value)| println!("{}: {}", name, value));
+    if let Ok(contact) = contact {
+        println!("{} {}, {} ({})", contact.first_name, contact.last_name, contact.company_name, contact.phone_number);
+    }
     Ok(())
 }
 
+fn complete_company(input: &str) -> Vec<String> {
+    ["Acme Corporation", "Globex", "Initech", "Umbrella Corporation", "Wayne Enterprises"]
+        .iter()
+        .filter(|company| company.to_lowercase().starts_with(&input.to_lowercase()))
+        .map(|company| company.to_string())
+        .collect()
+}
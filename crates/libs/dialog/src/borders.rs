@@ -19,14 +19,14 @@ impl Borders {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub enum BorderStyle {
     Single,
     #[default]
     Double
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct BorderChars {
     pub(crate) tl: char,
     pub(crate) tr: char,
@@ -40,7 +40,24 @@ pub(crate) struct BorderChars {
 
     pub(crate) left_intersect: char,
     pub(crate) right_intersect: char,
-    pub(crate) split: char
+    pub(crate) split: char,
+
+    pub(crate) guides: TreeGuides
+}
+
+/// Lines connecting the nodes of a tree, drawn in the style of the split border.
+#[derive(Debug, Clone)]
+pub(crate) struct TreeGuides {
+    pub(crate) vertical: char,
+    pub(crate) branch: char,
+    pub(crate) last: char,
+    pub(crate) horizontal: char
+}
+
+impl Default for TreeGuides {
+    fn default() -> Self {
+        BorderChars::default().guides
+    }
 }
 
 impl Default for BorderChars {
@@ -127,7 +144,12 @@ impl BorderChars {
             BorderStyle::Double => '═',
         };
 
-        Self { tl, tr, bl, br, top, left, right, bottom, left_intersect, right_intersect, split }
+        let guides = match &borders.split {
+            BorderStyle::Single => TreeGuides { vertical: '│', branch: '├', last: '└', horizontal: '─' },
+            BorderStyle::Double => TreeGuides { vertical: '║', branch: '╠', last: '╚', horizontal: '═' },
+        };
+
+        Self { tl, tr, bl, br, top, left, right, bottom, left_intersect, right_intersect, split, guides }
 
     }
 }
\ No newline at end of file
@@ -6,7 +6,12 @@ pub struct DialogColors {
     pub(crate) fill: Colors,
     pub(crate) overlay: Colors,
     pub(crate) fields: FieldColors,
-    pub(crate) buttons: ButtonColors
+    pub(crate) buttons: ButtonColors,
+    pub(crate) lists: ListColors,
+    pub(crate) gauges: GaugeColors,
+    pub(crate) text_boxes: TextBoxColors,
+    pub(crate) tables: TableColors,
+    pub(crate) tabs: TabColors
 }
 
 impl Default for DialogColors {
@@ -16,12 +21,18 @@ impl Default for DialogColors {
             fill: Colors::new(Color::White, Color::Black),
             overlay: Colors::new(Color::White, Color::Black),
             fields: Default::default(),
-            buttons: Default::default()
+            buttons: Default::default(),
+            lists: Default::default(),
+            gauges: Default::default(),
+            text_boxes: Default::default(),
+            tables: Default::default(),
+            tabs: Default::default()
         }
     }
 }
 
 impl DialogColors {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         border: Colors,
         fill: Colors,
@@ -38,20 +49,69 @@ impl DialogColors {
             overlay,
             fields: FieldColors::new(labels, inputs, input_indicators),
             buttons: ButtonColors::new(buttons, button_focus),
+            lists: Default::default(),
+            gauges: Default::default(),
+            text_boxes: Default::default(),
+            tables: Default::default(),
+            tabs: Default::default()
         }
     }
+
+    pub fn set_lists(mut self, lists: ListColors) -> Self {
+        self.lists = lists;
+
+        self
+    }
+
+    pub fn set_gauges(mut self, gauges: GaugeColors) -> Self {
+        self.gauges = gauges;
+
+        self
+    }
+
+    pub fn set_text_boxes(mut self, text_boxes: TextBoxColors) -> Self {
+        self.text_boxes = text_boxes;
+
+        self
+    }
+
+    pub fn set_tables(mut self, tables: TableColors) -> Self {
+        self.tables = tables;
+
+        self
+    }
+
+    pub fn set_tabs(mut self, tabs: TabColors) -> Self {
+        self.tabs = tabs;
+
+        self
+    }
+
+    pub fn set_ghost_text(mut self, ghost: Colors) -> Self {
+        self.fields.input.ghost = ghost;
+
+        self
+    }
+
+    pub fn set_suggestions(mut self, suggestions: ListColors) -> Self {
+        self.fields.suggestions = suggestions;
+
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct FieldColors {
     pub(crate) label: Colors,
     pub(crate) input: LineBufferColors,
+    pub(crate) suggestions: ListColors
 }
 impl FieldColors {
     pub fn new(label: Colors, input: Colors, indicators: Colors) -> Self {
         Self {
             label,
-            input: LineBufferColors::new(input, indicators)
+            input: LineBufferColors::new(input, indicators),
+            suggestions: Default::default()
         }
     }
 }
@@ -60,7 +120,8 @@ impl Default for FieldColors {
     fn default() -> Self {
         Self {
             label: Colors::new(Color::White, Color::Black),
-            input: Default::default()
+            input: Default::default(),
+            suggestions: Default::default()
         }
     }
 }
@@ -68,7 +129,8 @@ impl Default for FieldColors {
 #[derive(Debug, Clone)]
 pub struct LineBufferColors {
     pub(crate) focus: Colors,
-    pub(crate) input: Colors
+    pub(crate) input: Colors,
+    pub(crate) ghost: Colors
 }
 
 impl LineBufferColors {
@@ -76,6 +138,7 @@ impl LineBufferColors {
         Self {
             focus: indicators,
             input,
+            ghost: Colors::new(Color::DarkGrey, Color::Black)
         }
     }
 }
@@ -83,7 +146,8 @@ impl Default for LineBufferColors {
     fn default() -> Self {
         Self {
             focus: Colors::new(Color::White, Color::Black),
-            input: Colors::new(Color::White, Color::Black)
+            input: Colors::new(Color::White, Color::Black),
+            ghost: Colors::new(Color::DarkGrey, Color::Black)
         }
     }
 }
@@ -101,4 +165,121 @@ impl ButtonColors {
             button, focus 
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone)]
+pub struct ListColors {
+    pub(crate) item: Colors,
+    pub(crate) selected: Colors,
+}
+
+impl ListColors {
+    pub fn new(item: Colors, selected: Colors) -> Self {
+        Self {
+            item, selected
+        }
+    }
+}
+
+impl Default for ListColors {
+    fn default() -> Self {
+        Self {
+            item: Colors::new(Color::White, Color::DarkGrey),
+            selected: Colors::new(Color::Black, Color::White)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GaugeColors {
+    pub(crate) bar: Colors,
+    pub(crate) empty: Colors,
+}
+
+impl GaugeColors {
+    pub fn new(bar: Colors, empty: Colors) -> Self {
+        Self {
+            bar, empty
+        }
+    }
+}
+
+impl Default for GaugeColors {
+    fn default() -> Self {
+        Self {
+            bar: Colors::new(Color::Black, Color::White),
+            empty: Colors::new(Color::White, Color::DarkGrey)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TextBoxColors {
+    pub(crate) text: Colors,
+    pub(crate) highlight: Colors,
+}
+
+impl TextBoxColors {
+    pub fn new(text: Colors, highlight: Colors) -> Self {
+        Self {
+            text, highlight
+        }
+    }
+}
+
+impl Default for TextBoxColors {
+    fn default() -> Self {
+        Self {
+            text: Colors::new(Color::White, Color::Black),
+            highlight: Colors::new(Color::Black, Color::White)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TableColors {
+    pub(crate) header: Colors,
+    pub(crate) row: Colors,
+    pub(crate) selected: Colors,
+}
+
+impl TableColors {
+    pub fn new(header: Colors, row: Colors, selected: Colors) -> Self {
+        Self {
+            header, row, selected
+        }
+    }
+}
+
+impl Default for TableColors {
+    fn default() -> Self {
+        Self {
+            header: Colors::new(Color::Yellow, Color::DarkGrey),
+            row: Colors::new(Color::White, Color::DarkGrey),
+            selected: Colors::new(Color::Black, Color::White)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TabColors {
+    pub(crate) tab: Colors,
+    pub(crate) active: Colors,
+}
+
+impl TabColors {
+    pub fn new(tab: Colors, active: Colors) -> Self {
+        Self {
+            tab, active
+        }
+    }
+}
+
+impl Default for TabColors {
+    fn default() -> Self {
+        Self {
+            tab: Colors::new(Color::White, Color::DarkGrey),
+            active: Colors::new(Color::Black, Color::White)
+        }
+    }
+}
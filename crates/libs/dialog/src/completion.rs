@@ -0,0 +1,211 @@
+use std::{fmt::Debug, rc::Rc};
+
+const MAX_VISIBLE_SUGGESTIONS: usize = 5;
+
+type Provider = Rc<dyn Fn(&str) -> Vec<String>>;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum CompletionStyle {
+    /// Greyed out remainder of the first matching suggestion, accepted with Right or Tab at the end of the input.
+    #[default]
+    Inline,
+    /// List of suggestions below the field, navigated with Up/Down and accepted with Enter or Tab.
+    Popup
+}
+
+#[derive(Clone)]
+pub(crate) struct Completion {
+    provider: Provider,
+    pub(crate) style: CompletionStyle,
+    suggestions: Vec<String>,
+    selected: usize,
+    scroll: usize,
+    open: bool
+}
+
+impl Debug for Completion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Completion")
+            .field("style", &self.style)
+            .field("suggestions", &self.suggestions)
+            .field("selected", &self.selected)
+            .field("open", &self.open)
+            .finish()
+    }
+}
+
+impl Completion {
+    pub(crate) fn new(provider: impl Fn(&str) -> Vec<String> + 'static, style: CompletionStyle) -> Self {
+        Self {
+            provider: Rc::new(provider),
+            style,
+            suggestions: Vec::new(),
+            selected: 0,
+            scroll: 0,
+            open: false
+        }
+    }
+
+    pub(crate) fn update(&mut self, input: &str) {
+        self.suggestions = if input.is_empty() { Vec::new() } else { (self.provider)(input) };
+        self.selected = 0;
+        self.scroll = 0;
+        self.open = self.style == CompletionStyle::Popup && !self.suggestions.is_empty();
+    }
+
+    /// The part of the first suggestion that extends the current input, if any.
+    pub(crate) fn ghost<'a>(&'a self, input: &str) -> Option<&'a str> {
+        self.ghost_match(input).map(|(_, ghost)| ghost)
+    }
+
+    /// The full suggestion matching the current ghost text, in the suggestion's own case.
+    pub(crate) fn ghost_suggestion(&self, input: &str) -> Option<String> {
+        self.ghost_match(input).map(|(suggestion, _)| suggestion.clone())
+    }
+
+    // The first suggestion the input is a shorter prefix of, ignoring case, and the rest of it.
+    fn ghost_match<'a>(&'a self, input: &str) -> Option<(&'a String, &'a str)> {
+        if self.style != CompletionStyle::Inline || input.is_empty() {
+            return None;
+        }
+
+        self.suggestions
+            .iter()
+            .find_map(|s| strip_prefix_ignoring_case(s, input).filter(|rest| !rest.is_empty()).map(|rest| (s, rest)))
+    }
+
+    pub(crate) fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub(crate) fn close(&mut self) {
+        self.open = false;
+    }
+
+    pub(crate) fn select_next(&mut self) {
+        if self.selected + 1 < self.suggestions.len() {
+            self.selected += 1;
+        }
+        if self.selected >= self.scroll + MAX_VISIBLE_SUGGESTIONS {
+            self.scroll = self.selected + 1 - MAX_VISIBLE_SUGGESTIONS;
+        }
+    }
+
+    pub(crate) fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        }
+    }
+
+    pub(crate) fn selected(&self) -> Option<String> {
+        self.suggestions.get(self.selected).cloned()
+    }
+
+    /// Visible suggestions as `(text, is_selected)` pairs.
+    pub(crate) fn visible(&self) -> impl Iterator<Item = (&String, bool)> {
+        self.suggestions
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(MAX_VISIBLE_SUGGESTIONS)
+            .map(|(i, s)| (s, i == self.selected))
+    }
+}
+
+// Compared a character at a time, since changing case can change a character's length in bytes.
+fn strip_prefix_ignoring_case<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let mut chars = text.char_indices();
+    for c in prefix.chars() {
+        let (_, t) = chars.next()?;
+        if !c.to_lowercase().eq(t.to_lowercase()) {
+            return None;
+        }
+    }
+
+    Some(chars.next().map(|(i, _)| &text[i..]).unwrap_or(""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completion(suggestions: &[&str], style: CompletionStyle) -> Completion {
+        let suggestions = suggestions.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        Completion::new(move |_| suggestions.clone(), style)
+    }
+
+    #[test]
+    fn ghost_extends_the_input_ignoring_case() {
+        let mut completion = completion(&["Acme Corporation", "Acme"], CompletionStyle::Inline);
+        completion.update("acm");
+
+        assert_eq!(completion.ghost("acm"), Some("e Corporation"));
+        assert_eq!(completion.ghost_suggestion("acm").as_deref(), Some("Acme Corporation"));
+    }
+
+    #[test]
+    fn ghost_needs_something_left_to_add() {
+        let mut completion = completion(&["Acme"], CompletionStyle::Inline);
+        completion.update("acme");
+        assert_eq!(completion.ghost("acme"), None);
+
+        completion.update("b");
+        assert_eq!(completion.ghost("b"), None);
+        assert_eq!(completion.ghost(""), None);
+    }
+
+    #[test]
+    fn ghost_handles_case_changes_that_change_byte_lengths() {
+        // The Kelvin sign takes three bytes and lowercases to a one byte "k".
+        let mut completion = completion(&["\u{212A}elvin", "Ärger"], CompletionStyle::Inline);
+        completion.update("k");
+        assert_eq!(completion.ghost("k"), Some("elvin"));
+        assert_eq!(completion.ghost_suggestion("k").as_deref(), Some("\u{212A}elvin"));
+
+        completion.update("är");
+        assert_eq!(completion.ghost("är"), Some("ger"));
+    }
+
+    #[test]
+    fn popups_have_no_ghost() {
+        let mut completion = completion(&["Acme"], CompletionStyle::Popup);
+        completion.update("a");
+
+        assert!(completion.is_open());
+        assert_eq!(completion.ghost("a"), None);
+    }
+
+    #[test]
+    fn selection_scrolls_the_visible_suggestions() {
+        let names = ["a1", "a2", "a3", "a4", "a5", "a6", "a7"];
+        let mut completion = completion(&names, CompletionStyle::Popup);
+        completion.update("a");
+
+        let visible = |c: &Completion| c.visible().map(|(s, selected)| (s.clone(), selected)).collect::<Vec<_>>();
+        assert_eq!(visible(&completion).len(), MAX_VISIBLE_SUGGESTIONS);
+        assert_eq!(visible(&completion)[0], ("a1".to_string(), true));
+
+        (0..5).for_each(|_| completion.select_next());
+        assert_eq!(completion.selected().as_deref(), Some("a6"));
+        assert_eq!(visible(&completion).first().map(|(s, _)| s.as_str()), Some("a2"));
+        assert_eq!(visible(&completion).last(), Some(&("a6".to_string(), true)));
+
+        // Stops at the last suggestion.
+        (0..5).for_each(|_| completion.select_next());
+        assert_eq!(completion.selected().as_deref(), Some("a7"));
+
+        (0..10).for_each(|_| completion.select_previous());
+        assert_eq!(completion.selected().as_deref(), Some("a1"));
+        assert_eq!(visible(&completion)[0], ("a1".to_string(), true));
+    }
+
+    #[test]
+    fn empty_input_closes_the_popup() {
+        let mut completion = completion(&["a1"], CompletionStyle::Popup);
+        completion.update("");
+
+        assert!(!completion.is_open());
+        assert_eq!(completion.visible().count(), 0);
+    }
+}
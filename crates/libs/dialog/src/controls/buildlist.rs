@@ -0,0 +1,343 @@
+use std::io::stdout;
+
+use crossterm::{cursor::{Hide, MoveTo}, event::{KeyCode, KeyModifiers}, style::{Print, SetColors}, QueueableCommand};
+
+use crate::{colors::ListColors, dialog::{DialogReturnValue, TextMode}, utils::Position};
+
+use super::{list::{ListItem, VALUE_SEPARATOR}, Alignment, UIElement};
+
+const PANE_GAP: usize = 3;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum Pane {
+    #[default]
+    Available,
+    Selected
+}
+
+/// Two lists side by side, items move between them with Space or Enter and Left/Right switches panes.
+/// Shift or Ctrl with Up/Down moves an item within the selected pane. The value is the selected tags, in order.
+#[derive(Debug, Clone)]
+pub struct BuildList {
+    name: String,
+    tab_index: Option<usize>,
+    index: usize,
+    items: Vec<ListItem>,
+    // Indices into `items`, in the order they were picked.
+    selected: Vec<usize>,
+    rows: usize,
+    full_rows: usize,
+    pane: Pane,
+    cursors: [usize; 2],
+    scrolls: [usize; 2],
+    titles: [String; 2],
+    position: Position,
+    colors: ListColors
+}
+
+impl BuildList {
+    /// Items marked as selected start out in the selected pane.
+    pub fn new(name: impl Into<String>, items: Vec<ListItem>, rows: usize, tab_index: Option<usize>, index: usize) -> Self {
+        Self {
+            name: name.into(),
+            tab_index,
+            index,
+            selected: items.iter().enumerate().filter(|(_, i)| i.selected).map(|(i, _)| i).collect(),
+            items,
+            rows: rows.max(1),
+            full_rows: rows.max(1),
+            pane: Pane::default(),
+            cursors: [0; 2],
+            scrolls: [0; 2],
+            titles: ["Available".into(), "Selected".into()],
+            position: Position::default(),
+            colors: ListColors::default()
+        }
+    }
+
+    pub fn set_titles(mut self, available: impl Into<String>, selected: impl Into<String>) -> Self {
+        self.titles = [available.into(), selected.into()];
+
+        self
+    }
+
+    pub(crate) fn set_colors(&mut self, colors: ListColors) {
+        self.colors = colors;
+    }
+
+    pub(crate) fn get_field_index(&self) -> usize {
+        self.index
+    }
+
+    pub(crate) fn get_width(&self) -> usize {
+        self.pane_width() * 2 + PANE_GAP
+    }
+
+    pub(crate) fn get_height(&self) -> usize {
+        self.rows + 1
+    }
+
+    fn pane_width(&self) -> usize {
+        self.items
+            .iter()
+            .map(|i| label(i).chars().count())
+            .chain(self.titles.iter().map(|t| t.chars().count()))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn pane_items(&self, pane: Pane) -> Vec<usize> {
+        match pane {
+            Pane::Available => (0..self.items.len()).filter(|i| !self.selected.contains(i)).collect(),
+            Pane::Selected => self.selected.clone()
+        }
+    }
+
+    pub(crate) fn fit_height(&mut self, height: usize) {
+        self.rows = self.full_rows.min(height.saturating_sub(1)).max(1);
+        self.move_cursor(Pane::Available, self.cursors[Pane::Available as usize]);
+        self.move_cursor(Pane::Selected, self.cursors[Pane::Selected as usize]);
+    }
+
+    fn move_cursor(&mut self, pane: Pane, cursor: usize) {
+        let p = pane as usize;
+        let cursor = cursor.min(self.pane_items(pane).len().saturating_sub(1));
+
+        self.cursors[p] = cursor;
+        if cursor < self.scrolls[p] {
+            self.scrolls[p] = cursor;
+        } else if cursor >= self.scrolls[p] + self.rows {
+            self.scrolls[p] = cursor + 1 - self.rows;
+        }
+    }
+
+    // Moves the item under the cursor to the other pane, appending it to the selection.
+    fn transfer(&mut self) {
+        let cursor = self.cursors[self.pane as usize];
+        let Some(item) = self.pane_items(self.pane).get(cursor).copied() else { return };
+
+        match self.pane {
+            Pane::Available => self.selected.push(item),
+            Pane::Selected => self.selected.retain(|i| *i != item)
+        }
+        self.move_cursor(self.pane, cursor);
+    }
+
+    fn reorder(&mut self, down: bool) {
+        if self.pane != Pane::Selected {
+            return;
+        }
+
+        let cursor = self.cursors[Pane::Selected as usize];
+        let target = if down { cursor + 1 } else { cursor.wrapping_sub(1) };
+        if target < self.selected.len() {
+            self.selected.swap(cursor, target);
+            self.move_cursor(Pane::Selected, target);
+        }
+    }
+
+    fn draw_pane(&self, pane: Pane, x: usize) -> Result<(), std::io::Error> {
+        let p = pane as usize;
+        let width = self.pane_width();
+        let items = self.pane_items(pane);
+
+        stdout()
+            .queue(SetColors(self.colors.item))?
+            .queue(MoveTo(x as u16, self.position.y as u16))?
+            .queue(Print(Alignment::Center.apply(&self.titles[p], width)))?;
+
+        for row in 0..self.rows {
+            let index = self.scrolls[p] + row;
+            let colors = if pane == self.pane && index == self.cursors[p] && index < items.len() { self.colors.selected } else { self.colors.item };
+            let text = items.get(index).map(|i| label(&self.items[*i])).unwrap_or("");
+
+            stdout()
+                .queue(SetColors(colors))?
+                .queue(MoveTo(x as u16, (self.position.y + 1 + row) as u16))?
+                .queue(Print(Alignment::Left.apply(text, width)))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn label(item: &ListItem) -> &str {
+    if item.description.is_empty() { &item.tag } else { &item.description }
+}
+
+impl UIElement for BuildList {
+    fn draw(&self) -> Result<(), std::io::Error> {
+        self.draw_pane(Pane::Available, self.position.x)?;
+        self.draw_pane(Pane::Selected, self.position.x + self.pane_width() + PANE_GAP)?;
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, code: KeyCode, modifiers: KeyModifiers, mode: TextMode) -> Result<DialogReturnValue, std::io::Error> {
+        let cursor = self.cursors[self.pane as usize];
+        let reordering = modifiers.intersects(KeyModifiers::SHIFT | KeyModifiers::CONTROL);
+
+        match code {
+            KeyCode::Up if reordering => self.reorder(false),
+            KeyCode::Down if reordering => self.reorder(true),
+            KeyCode::Up => self.move_cursor(self.pane, cursor.saturating_sub(1)),
+            KeyCode::Down => self.move_cursor(self.pane, cursor + 1),
+            KeyCode::PageUp => self.move_cursor(self.pane, cursor.saturating_sub(self.rows)),
+            KeyCode::PageDown => self.move_cursor(self.pane, cursor + self.rows),
+            KeyCode::Home => self.move_cursor(self.pane, 0),
+            KeyCode::End => self.move_cursor(self.pane, usize::MAX),
+            KeyCode::Left => self.pane = Pane::Available,
+            KeyCode::Right => self.pane = Pane::Selected,
+            KeyCode::Char(' ') | KeyCode::Enter => self.transfer(),
+            _ => return Ok(DialogReturnValue::default())
+        }
+        self.draw()?;
+        self.show_focus_indicator(mode)?;
+
+        Ok(DialogReturnValue::default())
+    }
+
+    // Enter only submits the dialog from an empty pane.
+    fn captures_input(&self, code: KeyCode, _modifiers: KeyModifiers) -> bool {
+        code == KeyCode::Enter && self.pane_items(self.pane).get(self.cursors[self.pane as usize]).is_some()
+    }
+
+    fn show_focus_indicator(&self, _mode: TextMode) -> Result<(), std::io::Error> {
+        let p = self.pane as usize;
+        let x = match self.pane {
+            Pane::Available => self.position.x,
+            Pane::Selected => self.position.x + self.pane_width() + PANE_GAP
+        };
+
+        stdout()
+            .queue(Hide)?
+            .queue(MoveTo(x as u16, (self.position.y + 1 + self.cursors[p] - self.scrolls[p]) as u16))?;
+
+        Ok(())
+    }
+
+    fn hide_focus_indicator(&mut self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+
+    fn get_position(&self) -> Position {
+        self.position.clone()
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn get_tab_index(&self) -> Option<usize> {
+        self.tab_index
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_value(&self) -> Option<(String, String)> {
+        let value = self.selected
+            .iter()
+            .map(|i| self.items[*i].tag.as_str())
+            .collect::<Vec<_>>()
+            .join(&VALUE_SEPARATOR.to_string());
+
+        Some((self.name.clone(), value))
+    }
+
+    fn set_value(&mut self, value: &str) {
+        self.selected = value
+            .split(VALUE_SEPARATOR)
+            .filter_map(|tag| self.items.iter().position(|i| i.tag == tag))
+            .collect();
+        self.cursors = [0; 2];
+        self.scrolls = [0; 2];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_list() -> BuildList {
+        let items = ["a", "b", "c", "d"].into_iter().map(|tag| ListItem::new(tag, "", tag == "c")).collect();
+        BuildList::new("tags", items, 2, None, 0)
+    }
+
+    fn value(list: &BuildList) -> String {
+        list.get_value().unwrap().1.replace(VALUE_SEPARATOR, ",")
+    }
+
+    #[test]
+    fn selected_items_start_in_the_selected_pane() {
+        let list = build_list();
+
+        assert_eq!(list.pane_items(Pane::Available), [0, 1, 3]);
+        assert_eq!(value(&list), "c");
+    }
+
+    #[test]
+    fn transfer_appends_in_the_order_picked() {
+        let mut list = build_list();
+        list.move_cursor(Pane::Available, 2);
+        list.transfer();
+        list.move_cursor(Pane::Available, 0);
+        list.transfer();
+
+        assert_eq!(value(&list), "c,d,a");
+        assert_eq!(list.pane_items(Pane::Available), [1]);
+    }
+
+    #[test]
+    fn transfer_back_keeps_the_cursor_in_range() {
+        let mut list = build_list();
+        list.move_cursor(Pane::Available, 0);
+        list.transfer();
+        list.pane = Pane::Selected;
+        list.move_cursor(Pane::Selected, 1);
+        list.transfer();
+
+        assert_eq!(value(&list), "c");
+        assert_eq!(list.cursors[Pane::Selected as usize], 0);
+        assert_eq!(list.pane_items(Pane::Available), [0, 1, 3]);
+
+        list.transfer();
+        list.transfer();
+        assert_eq!(value(&list), "");
+    }
+
+    #[test]
+    fn reorder_moves_within_the_selection() {
+        let mut list = build_list();
+        list.set_value(&["a", "b", "c"].join(&VALUE_SEPARATOR.to_string()));
+        list.pane = Pane::Selected;
+
+        list.reorder(true);
+        assert_eq!((value(&list).as_str(), list.cursors[1]), ("b,a,c", 1));
+        list.reorder(true);
+        list.reorder(true);
+        assert_eq!((value(&list).as_str(), list.cursors[1], list.scrolls[1]), ("b,c,a", 2, 1));
+        list.move_cursor(Pane::Selected, 0);
+        list.reorder(false);
+        assert_eq!(value(&list), "b,c,a");
+    }
+
+    #[test]
+    fn reorder_ignores_the_available_pane() {
+        let mut list = build_list();
+        list.set_value(&["a", "b"].join(&VALUE_SEPARATOR.to_string()));
+        list.reorder(true);
+
+        assert_eq!(value(&list), "a,b");
+    }
+
+    #[test]
+    fn set_value_keeps_the_given_order_and_skips_unknown_tags() {
+        let mut list = build_list();
+        list.set_value(&["d", "x", "b"].join(&VALUE_SEPARATOR.to_string()));
+
+        assert_eq!(value(&list), "d,b");
+        assert_eq!(list.pane_items(Pane::Available), [0, 2]);
+    }
+}
@@ -6,7 +6,7 @@ use crossterm::style::{Color, Colors, Print, SetColors};
 use crossterm::QueueableCommand;
 
 use crate::colors::ButtonColors;
-use crate::dialog::{ButtonCount, DialogResult, DialogReturnValue, TextMode};
+use crate::dialog::{DialogResult, DialogReturnValue, TextMode};
 use crate::utils::Position;
 
 use super::UIElement;
@@ -19,7 +19,8 @@ pub struct Button {
     pub(crate) name: String,
     tab_index: Option<usize>,
     result: DialogResult,
-    pub(crate) index: ButtonCount,
+    // Place in the button bar, which follows the order buttons were added rather than tab order.
+    pub(crate) order: usize,
     position: Position,
     colors: ButtonColors
 }
@@ -34,12 +35,12 @@ impl Default for ButtonColors {
 }
 
 impl Button {
-    pub fn new(name: impl Into<String>, tab_index: Option<usize>, result: DialogResult, index: ButtonCount) -> Self {
+    pub fn new(name: impl Into<String>, tab_index: Option<usize>, result: DialogResult) -> Self {
         Self {
             name: name.into(),
             tab_index,
             result,
-            index,
+            order: 0,
             position: Position::default(),
             colors: ButtonColors::default()
          }
@@ -48,6 +49,10 @@ impl Button {
     pub(crate) fn set_colors(&mut self, colors: ButtonColors) {
         self.colors = colors;
     }
+
+    pub(crate) fn get_result(&self) -> &DialogResult {
+        &self.result
+    }
 }
 
 impl UIElement for Button {
@@ -60,11 +65,17 @@ impl UIElement for Button {
         Ok(())
     }
 
+    // Enter presses the focused button rather than submitting the dialog, as in whiptail.
+    fn captures_input(&self, code: KeyCode, _: KeyModifiers) -> bool {
+        code == KeyCode::Enter
+    }
+
     fn handle_input(&mut self, code: KeyCode, _: KeyModifiers, _: TextMode) -> Result<DialogReturnValue, std::io::Error> {
-        if let KeyCode::Char(' ') = code {
+        if let KeyCode::Char(' ') | KeyCode::Enter = code {
             Ok(DialogReturnValue {
                 should_quit: true,
                 dialog_result: Some(self.result.clone()),
+                ..Default::default()
             })
         } else {
             Ok(DialogReturnValue::default())
@@ -93,6 +104,10 @@ impl UIElement for Button {
         Ok(())
     }
 
+    fn get_position(&self) -> Position {
+        self.position.clone()
+    }
+
     fn set_position(&mut self, position: Position) {
         self.position = position
     }
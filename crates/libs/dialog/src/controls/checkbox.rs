@@ -0,0 +1,124 @@
+use std::io::stdout;
+
+use crossterm::{cursor::{Hide, MoveTo, Show}, event::{KeyCode, KeyModifiers}, style::{Color, Colors, Print, SetColors}, QueueableCommand};
+
+use crate::{colors::FieldColors, dialog::{DialogReturnValue, TextMode}, utils::Position};
+
+use super::UIElement;
+
+#[derive(Debug, Clone)]
+pub struct Checkbox {
+    name: String,
+    tab_index: Option<usize>,
+    index: usize,
+    checked: bool,
+    position: Position,
+    label_colors: Colors,
+    input_colors: Colors
+}
+
+impl Checkbox {
+    pub fn new(name: impl Into<String>, checked: bool, tab_index: Option<usize>, index: usize) -> Self {
+        Self {
+            name: name.into(),
+            tab_index,
+            index,
+            checked,
+            position: Position::default(),
+            label_colors: Colors::new(Color::White, Color::Black),
+            input_colors: Colors::new(Color::White, Color::Black)
+        }
+    }
+
+    pub(crate) fn set_colors(&mut self, colors: FieldColors) {
+        self.label_colors = colors.label;
+        self.input_colors = colors.input.input;
+    }
+
+    pub(crate) fn get_field_index(&self) -> usize {
+        self.index
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    pub(crate) fn get_label_width(&self) -> usize {
+        self.name.len() + 2
+    }
+
+    fn box_position(&self) -> Position {
+        (self.position.x + self.get_label_width(), self.position.y).into()
+    }
+
+    fn draw_box(&self) -> Result<(), std::io::Error> {
+        let pos = self.box_position();
+        stdout()
+            .queue(SetColors(self.input_colors))?
+            .queue(MoveTo(pos.x as u16, pos.y as u16))?
+            .queue(Print(if self.checked { "[x]" } else { "[ ]" }))?
+            .queue(MoveTo(pos.x as u16 + 1, pos.y as u16))?;
+
+        Ok(())
+    }
+}
+
+impl UIElement for Checkbox {
+    fn draw(&self) -> Result<(), std::io::Error> {
+        stdout()
+            .queue(SetColors(self.label_colors))?
+            .queue(MoveTo(self.position.x as u16, self.position.y as u16))?
+            .queue(Print(format!("{}: ", self.name)))?;
+        self.draw_box()?;
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, code: KeyCode, _modifiers: KeyModifiers, _mode: TextMode) -> Result<DialogReturnValue, std::io::Error> {
+        if let KeyCode::Char(' ') = code {
+            self.checked = !self.checked;
+            self.draw_box()?;
+        }
+
+        Ok(DialogReturnValue::default())
+    }
+
+    fn show_focus_indicator(&self, _mode: TextMode) -> Result<(), std::io::Error> {
+        let pos = self.box_position();
+        stdout()
+            .queue(Show)?
+            .queue(MoveTo(pos.x as u16 + 1, pos.y as u16))?;
+
+        Ok(())
+    }
+
+    fn hide_focus_indicator(&mut self) -> Result<(), std::io::Error> {
+        stdout().queue(Hide)?;
+
+        Ok(())
+    }
+
+    fn get_position(&self) -> Position {
+        self.position.clone()
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn get_tab_index(&self) -> Option<usize> {
+        self.tab_index
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_value(&self) -> Option<(String, String)> {
+        Some((self.name.clone(), self.checked.to_string()))
+    }
+
+    fn set_value(&mut self, value: &str) {
+        self.checked = matches!(value.trim().to_lowercase().as_str(), "true" | "yes" | "on" | "1");
+    }
+}
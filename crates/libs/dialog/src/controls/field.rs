@@ -1,5 +1,5 @@
 // region:    -- Fields
-use crate::{colors::FieldColors, dialog::{DialogReturnValue, TextMode}, line_buffer::LineBuffer, utils::Position};
+use crate::{colors::{FieldColors, ListColors}, completion::{Completion, CompletionStyle}, dialog::{DialogReturnValue, TextMode}, line_buffer::LineBuffer, utils::Position, validation::Validation};
 
 use std::io::stdout;
 
@@ -10,27 +10,44 @@ use super::UIElement;
 #[derive(Debug, Clone)]
 pub struct Field {
     name: String,
+    label: Option<String>,
     display_len: usize,
     tab_index: Option<usize>,
     index: usize,
     value: String,
     position: Position,
     line_buffer: LineBuffer,
-    label_colors: Colors
-    
+    label_colors: Colors,
+    completion: Option<Completion>,
+    suggestion_colors: ListColors,
+    history_key: Option<String>,
+    history: Vec<String>,
+    history_index: Option<usize>,
+    draft: String,
+    numeric: bool,
+    validations: Vec<Validation>
 }
 
 impl Default for Field {
     fn default() -> Self {
         Self {
             name: Default::default(),
+            label: None,
             display_len: Default::default(),
             tab_index: Default::default(),
             index: Default::default(),
             value: Default::default(),
             position: Default::default(),
             line_buffer: Default::default(),
-            label_colors: Colors::new(Color::White, Color::Black)
+            label_colors: Colors::new(Color::White, Color::Black),
+            completion: None,
+            suggestion_colors: Default::default(),
+            history_key: None,
+            history: Vec::new(),
+            history_index: None,
+            draft: String::new(),
+            numeric: false,
+            validations: Vec::new()
         }
     }
 }
@@ -39,8 +56,10 @@ impl Default for Field {
 
 impl Field {
     pub fn new(name: impl Into<String>, display_len: usize, input_len: usize, tab_index: Option<usize>, index: usize) -> Self {
+        let name = name.into();
         Self {
-            name: name.into(),
+            label: Some(name.clone()).filter(|name| !name.is_empty()),
+            name,
             display_len,
             tab_index,
             index,
@@ -48,19 +67,169 @@ impl Field {
             line_buffer: LineBuffer::new(display_len, input_len, (0, 0).into(), '_'),
             position: (0, 0).into(),
             label_colors: Colors::new(Color::White, Color::Black),
+            completion: None,
+            suggestion_colors: Default::default(),
+            history_key: None,
+            history: Vec::new(),
+            history_index: None,
+            draft: String::new(),
+            numeric: false,
+            validations: Vec::new()
         }
     }
 
+    pub fn set_completion(mut self, provider: impl Fn(&str) -> Vec<String> + 'static, style: CompletionStyle) -> Self {
+        self.completion = Some(Completion::new(provider, style));
+
+        self
+    }
+
+    /// Text shown before the input, the field's name by default. `None` leaves the input unlabelled
+    /// while the value is still returned under the name.
+    pub fn set_label(mut self, label: Option<String>) -> Self {
+        self.label = label;
+
+        self
+    }
+
+    pub fn set_initial_value(mut self, value: impl Into<String>) -> Self {
+        UIElement::set_value(&mut self, &value.into());
+
+        self
+    }
+
+    /// Only accept characters that can appear in a number.
+    pub fn set_numeric(mut self, numeric: bool) -> Self {
+        self.numeric = numeric;
+
+        self
+    }
+
+    /// Hide the input behind `*`, e.g. for passwords.
+    pub fn set_masked(mut self, masked: bool) -> Self {
+        self.line_buffer.set_mask(masked.then_some('*'));
+
+        self
+    }
+
+    pub fn add_validation(mut self, validation: Validation) -> Self {
+        self.validations.push(validation);
+
+        self
+    }
+
+    /// Recalls earlier values stored under `key` with Up/Down or Alt+Up/Down. While a completion popup is open those
+    /// keys move through the suggestions instead.
+    pub fn set_history(mut self, key: impl Into<String>) -> Self {
+        self.history_key = Some(key.into());
+
+        self
+    }
+
+    pub(crate) fn move_cursor_to_end(&mut self) {
+        self.line_buffer.set_pos(self.value.chars().count());
+    }
+
+    pub(crate) fn get_history_key(&self) -> Option<&str> {
+        self.history_key.as_deref()
+    }
+
+    pub(crate) fn load_history(&mut self, entries: &[String]) {
+        self.history = entries.to_vec();
+        self.history_index = None;
+    }
+
+    fn history_previous(&mut self) -> Result<(), std::io::Error> {
+        let index = match self.history_index {
+            None if self.history.is_empty() => return Ok(()),
+            None => {
+                self.draft = self.value.clone();
+                self.history.len() - 1
+            },
+            Some(index) => index.saturating_sub(1)
+        };
+
+        self.history_index = Some(index);
+        self.show_history_value(self.history[index].clone())
+    }
+
+    fn history_next(&mut self) -> Result<(), std::io::Error> {
+        let value = match self.history_index {
+            None => return Ok(()),
+            Some(index) if index + 1 < self.history.len() => {
+                self.history_index = Some(index + 1);
+                self.history[index + 1].clone()
+            },
+            Some(_) => {
+                self.history_index = None;
+                self.draft.clone()
+            }
+        };
+
+        self.show_history_value(value)
+    }
+
+    fn show_history_value(&mut self, value: String) -> Result<(), std::io::Error> {
+        self.line_buffer.set_value(&value);
+        self.value = self.line_buffer.buffer.clone();
+        self.line_buffer.draw()
+    }
+
     pub(crate) fn set_colors(&mut self, colors: FieldColors) {
         self.label_colors = colors.label;
+        self.suggestion_colors = colors.suggestions;
         self.line_buffer.set_colors(colors.input);
     }
 
+    fn accept_completion(&mut self, value: String) -> Result<(), std::io::Error> {
+        self.line_buffer.set_value(&value);
+        self.value = self.line_buffer.buffer.clone();
+        if let Some(completion) = &mut self.completion {
+            completion.update(&self.value);
+            completion.close();
+        }
+        self.line_buffer.draw()?;
+
+        Ok(())
+    }
+
+    fn draw_completion(&self) -> Result<(), std::io::Error> {
+        let Some(completion) = &self.completion else {
+            return Ok(());
+        };
+
+        if completion.is_open() {
+            let pos = self.line_buffer.get_position();
+            for (row, (suggestion, selected)) in completion.visible().enumerate() {
+                let colors = if selected { self.suggestion_colors.selected } else { self.suggestion_colors.item };
+                let text: String = suggestion.chars().take(self.display_len).collect();
+                stdout()
+                    .queue(SetColors(colors))?
+                    .queue(MoveTo(pos.x as u16, (pos.y + row + 1) as u16))?
+                    .queue(Print(format!("{:<width$}", text, width = self.display_len)))?;
+            }
+            self.line_buffer.draw()?;
+        } else if let Some(ghost) = completion.ghost(&self.value) {
+            self.line_buffer.draw_ghost(ghost)?;
+        }
+
+        Ok(())
+    }
+
+    // A field without a label has its input start at the field's position.
+    pub(crate) fn get_label_width(&self) -> usize {
+        self.label.as_ref().map(|label| label.len() + 2).unwrap_or(0)
+    }
+
     fn draw_label(&self) -> Result<(), std::io::Error> {
+        let Some(label) = &self.label else {
+            return Ok(());
+        };
+
         stdout()
             .queue(SetColors(self.label_colors))?
             .queue(MoveTo(self.position.x as u16, self.position.y as u16))?
-            .queue(Print(format!("{}: ", self.name)))?;
+            .queue(Print(format!("{}: ", label)))?;
 
         Ok(())
     }
@@ -84,12 +253,77 @@ impl UIElement for Field {
     }
 
     fn handle_input(&mut self, code: KeyCode, modifiers: KeyModifiers, mode: TextMode) -> Result<DialogReturnValue, std::io::Error> {
+        if let Some(completion) = &mut self.completion {
+            if completion.is_open() {
+                match code {
+                    KeyCode::Up => completion.select_previous(),
+                    KeyCode::Down => completion.select_next(),
+                    KeyCode::Enter | KeyCode::Tab => {
+                        if let Some(selected) = completion.selected() {
+                            self.accept_completion(selected)?;
+                        }
+                        return Ok(DialogReturnValue { redraw: true, ..Default::default() });
+                    },
+                    KeyCode::Esc | KeyCode::BackTab => {
+                        completion.close();
+                        return Ok(DialogReturnValue { redraw: true, ..Default::default() });
+                    },
+                    _ => {}
+                }
+                if matches!(code, KeyCode::Up | KeyCode::Down) {
+                    self.draw_completion()?;
+                    return Ok(DialogReturnValue::default());
+                }
+            } else if matches!(code, KeyCode::Right | KeyCode::Tab) && self.line_buffer.is_cursor_at_end() {
+                if let Some(suggestion) = completion.ghost_suggestion(&self.value) {
+                    self.accept_completion(suggestion)?;
+                    return Ok(DialogReturnValue::default());
+                }
+            }
+        }
+
+        if let (true, KeyCode::Char(c)) = (self.numeric, code) {
+            if !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.')) {
+                return Ok(DialogReturnValue::default());
+            }
+        }
+
+        if self.history_key.is_some() && matches!(modifiers, KeyModifiers::NONE | KeyModifiers::ALT) {
+            match code {
+                KeyCode::Up => return self.history_previous().map(|_| DialogReturnValue::default()),
+                KeyCode::Down => return self.history_next().map(|_| DialogReturnValue::default()),
+                _ => {}
+            }
+        }
+
+        let was_open = self.completion.as_ref().is_some_and(|c| c.is_open());
         self.line_buffer.handle_input(code, modifiers, mode)?;
-        self.value = self.line_buffer.buffer.clone();
+
+        if self.value != self.line_buffer.buffer {
+            self.value = self.line_buffer.buffer.clone();
+            self.history_index = None;
+            if let Some(completion) = &mut self.completion {
+                completion.update(&self.value);
+            }
+        }
+
+        if was_open {
+            // The popup may have shrunk or closed, so whatever it covered has to be repainted.
+            return Ok(DialogReturnValue { redraw: true, ..Default::default() });
+        }
+        self.draw_completion()?;
 
         Ok(DialogReturnValue::default())
     }
 
+    fn captures_input(&self, code: KeyCode, _modifiers: KeyModifiers) -> bool {
+        match &self.completion {
+            Some(completion) if completion.is_open() => matches!(code, KeyCode::Up | KeyCode::Down | KeyCode::Enter | KeyCode::Esc | KeyCode::Tab | KeyCode::BackTab),
+            Some(completion) => code == KeyCode::Tab && self.line_buffer.is_cursor_at_end() && completion.ghost(&self.value).is_some(),
+            None => false
+        }
+    }
+
     fn show_focus_indicator(&self, _mode: TextMode) -> Result<(), std::io::Error> {
         let pos = self.line_buffer.get_position();
 
@@ -97,6 +331,10 @@ impl UIElement for Field {
             .queue(Show)?
             .queue(MoveTo(pos.x as u16, pos.y as u16))?;
 
+        if self.completion.as_ref().is_some_and(|c| c.is_open()) {
+            self.draw_completion()?;
+        }
+
         Ok(())
     }
 
@@ -108,9 +346,13 @@ impl UIElement for Field {
         Ok(())
     }
 
+    fn get_position(&self) -> Position {
+        self.position.clone()
+    }
+
     fn set_position(&mut self, position: Position) {
         self.position = position.clone();
-        let pos = Position { x: position.x+self.name.len()+2, y: position.y };
+        let pos = Position { x: position.x+self.get_label_width(), y: position.y };
         self.line_buffer.set_position(pos);
     }
 
@@ -125,4 +367,16 @@ impl UIElement for Field {
     fn get_name(&self) -> String {
         self.name.clone()
     }
+
+    fn validate(&self) -> Result<(), String> {
+        self.validations
+            .iter()
+            .try_for_each(|v| v.validate(&self.name, &self.value))
+    }
+
+    fn set_value(&mut self, value: &str) {
+        self.line_buffer.set_value(value);
+        self.line_buffer.set_pos(0);
+        self.value = self.line_buffer.buffer.clone();
+    }
 }
\ No newline at end of file
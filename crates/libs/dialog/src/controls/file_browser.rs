@@ -0,0 +1,571 @@
+use std::{fs, io::stdout, path::{Path, PathBuf, MAIN_SEPARATOR, MAIN_SEPARATOR_STR}};
+
+use crossterm::{cursor::{Hide, MoveTo}, event::{KeyCode, KeyModifiers}, style::{Print, SetColors}, QueueableCommand};
+
+use crate::{colors::{FieldColors, ListColors}, completion::CompletionStyle, dialog::{DialogReturnValue, TextMode}, utils::Position};
+
+use super::{field::Field, Alignment, UIElement};
+
+const SIZE_WIDTH: usize = 7;
+const MAX_PATH_LEN: usize = 4096;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum FileMode {
+    /// The path has to be an existing file.
+    #[default]
+    Open,
+    /// Any file name. Enter on an existing file asks whether to overwrite it.
+    Save,
+    /// The path has to be an existing directory, only directories are listed.
+    Directory
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum Part {
+    #[default]
+    Path,
+    Entries
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    name: String,
+    is_dir: bool,
+    size: u64
+}
+
+/// A path field above a listing of the current directory.
+///
+/// Tab moves between the two and completes paths in the field. In the listing Enter opens a directory,
+/// Backspace goes up a level and `.` shows or hides hidden files. The value is the path in the field.
+#[derive(Debug, Clone)]
+pub struct FileBrowser {
+    name: String,
+    tab_index: Option<usize>,
+    index: usize,
+    mode: FileMode,
+    dir: PathBuf,
+    filters: Vec<String>,
+    show_hidden: bool,
+    width: usize,
+    rows: usize,
+    full_rows: usize,
+    path: Field,
+    entries: Vec<Entry>,
+    cursor: usize,
+    scroll: usize,
+    focus: Part,
+    // Set once overwriting the file in the path is confirmed, cleared whenever the path changes.
+    overwrite: bool,
+    // Asking whether to overwrite the file in the path.
+    confirming: bool,
+    position: Position,
+    colors: ListColors
+}
+
+impl FileBrowser {
+    /// `start` can be a directory or a file within the directory to show.
+    pub fn new(name: impl Into<String>, start: impl AsRef<Path>, mode: FileMode, width: usize, rows: usize, tab_index: Option<usize>, index: usize) -> Self {
+        let width = width.max(SIZE_WIDTH * 2);
+        let start = fs::canonicalize(start.as_ref()).unwrap_or_else(|_| start.as_ref().to_path_buf());
+        let directories_only = mode == FileMode::Directory;
+
+        let mut browser = Self {
+            name: name.into(),
+            tab_index,
+            index,
+            mode,
+            dir: PathBuf::new(),
+            filters: Vec::new(),
+            show_hidden: false,
+            width,
+            rows: rows.max(1),
+            full_rows: rows.max(1),
+            path: Field::new("", width, MAX_PATH_LEN, None, 0)
+                .set_completion(move |input| complete_path(input, directories_only), CompletionStyle::Inline),
+            entries: Vec::new(),
+            cursor: 0,
+            scroll: 0,
+            focus: Part::default(),
+            overwrite: false,
+            confirming: false,
+            position: Position::default(),
+            colors: ListColors::default()
+        };
+
+        match start.is_dir() {
+            true => browser.open(start),
+            false => {
+                browser.open(start.parent().map(Path::to_path_buf).unwrap_or_default());
+                browser.set_path(&start.to_string_lossy());
+            }
+        }
+        browser
+    }
+
+    /// Only lists files matching one of the glob patterns, e.g. `*.toml`. Directories are always listed.
+    pub fn set_filters<S: Into<String>>(mut self, filters: impl IntoIterator<Item = S>) -> Self {
+        self.filters = filters.into_iter().map(Into::into).collect();
+        self.load();
+
+        self
+    }
+
+    pub fn set_show_hidden(mut self, show_hidden: bool) -> Self {
+        self.show_hidden = show_hidden;
+        self.load();
+
+        self
+    }
+
+    pub(crate) fn set_colors(&mut self, fields: FieldColors, lists: ListColors) {
+        self.path.set_colors(fields);
+        self.colors = lists;
+    }
+
+    pub(crate) fn get_field_index(&self) -> usize {
+        self.index
+    }
+
+    pub(crate) fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub(crate) fn get_height(&self) -> usize {
+        self.rows + 2
+    }
+
+    fn load(&mut self) {
+        let mut entries = fs::read_dir(&self.dir)
+            .map(|read| read
+                .filter_map(Result::ok)
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    // Follows symlinks, so links to directories can be opened.
+                    let metadata = fs::metadata(entry.path()).ok()?;
+                    Some(Entry { name, is_dir: metadata.is_dir(), size: metadata.len() })
+                })
+                .filter(|e| self.show_hidden || !e.name.starts_with('.'))
+                .filter(|e| e.is_dir || (self.mode != FileMode::Directory && self.matches_filters(&e.name)))
+                .collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+        if self.dir.parent().is_some() {
+            entries.insert(0, Entry { name: "..".into(), is_dir: true, size: 0 });
+        }
+
+        self.entries = entries;
+        self.move_cursor(self.cursor);
+    }
+
+    fn matches_filters(&self, name: &str) -> bool {
+        self.filters.is_empty() || self.filters.iter().any(|pattern| glob_match(pattern, name))
+    }
+
+    fn open(&mut self, dir: PathBuf) {
+        // A file name typed while saving is carried over to the new directory.
+        let file_name = match self.mode {
+            FileMode::Save => Path::new(&self.path_value()).file_name().filter(|_| !self.path_value().ends_with(MAIN_SEPARATOR)).map(|n| n.to_string_lossy().into_owned()),
+            _ => None
+        };
+
+        self.dir = dir;
+        self.cursor = 0;
+        self.scroll = 0;
+        self.load();
+
+        let dir = dir_string(&self.dir);
+        self.set_path(&format!("{}{}", dir, file_name.unwrap_or_default()));
+    }
+
+    fn open_entry(&mut self) {
+        let Some(entry) = self.entries.get(self.cursor).filter(|e| e.is_dir) else { return };
+
+        let dir = match entry.name.as_str() {
+            ".." => self.dir.parent().map(Path::to_path_buf).unwrap_or_else(|| self.dir.clone()),
+            name => self.dir.join(name)
+        };
+        self.open(dir);
+    }
+
+    fn set_path(&mut self, path: &str) {
+        self.overwrite = false;
+        self.path.set_value(path);
+        self.path.move_cursor_to_end();
+    }
+
+    fn path_value(&self) -> String {
+        self.path.get_value().map(|(_, value)| value).unwrap_or_default()
+    }
+
+    pub(crate) fn fit_height(&mut self, height: usize) {
+        self.rows = self.full_rows.min(height.saturating_sub(2)).max(1);
+        self.move_cursor(self.cursor);
+    }
+
+    fn move_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.entries.len().saturating_sub(1));
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + self.rows {
+            self.scroll = self.cursor + 1 - self.rows;
+        }
+    }
+
+    // Keeps the field in step with the highlighted entry.
+    fn select_entry(&mut self, cursor: usize) {
+        self.move_cursor(cursor);
+
+        let path = match self.entries.get(self.cursor) {
+            Some(entry) if entry.name != ".." => {
+                let path = dir_string(&self.dir) + &entry.name;
+                if entry.is_dir { path + MAIN_SEPARATOR_STR } else { path }
+            },
+            _ => dir_string(&self.dir)
+        };
+        self.set_path(&path);
+    }
+
+    // Saving over an existing file has to be confirmed first.
+    fn needs_confirmation(&self) -> bool {
+        let value = self.path_value();
+        let path = Path::new(&value);
+        self.mode == FileMode::Save && !self.overwrite && path.exists() && !path.is_dir()
+    }
+
+    fn typed_dir(&self) -> Option<PathBuf> {
+        let path = PathBuf::from(self.path_value());
+        path.is_dir().then_some(path)
+    }
+
+    fn format_entry(&self, entry: &Entry) -> String {
+        let name_width = self.width - SIZE_WIDTH - 1;
+
+        match entry.is_dir {
+            true => Alignment::Left.apply(&format!("{}{}", entry.name, MAIN_SEPARATOR), self.width),
+            false => format!("{} {}", Alignment::Left.apply(&entry.name, name_width), Alignment::Right.apply(&format_size(entry.size), SIZE_WIDTH))
+        }
+    }
+
+    fn draw_entries(&self) -> Result<(), std::io::Error> {
+        for row in 0..self.rows {
+            let index = self.scroll + row;
+            let (colors, text) = match self.entries.get(index) {
+                // The overwrite prompt takes the place of the first row.
+                _ if row == 0 && self.confirming => {
+                    let value = self.path_value();
+                    let name = Path::new(&value).file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or(value);
+                    (self.colors.selected, Alignment::Left.apply(&format!("Overwrite {}? (y/n)", name), self.width))
+                },
+                Some(entry) if index == self.cursor && self.focus == Part::Entries => (self.colors.selected, self.format_entry(entry)),
+                Some(entry) => (self.colors.item, self.format_entry(entry)),
+                None => (self.colors.item, " ".repeat(self.width))
+            };
+
+            stdout()
+                .queue(SetColors(colors))?
+                .queue(MoveTo(self.position.x as u16, (self.position.y + 2 + row) as u16))?
+                .queue(Print(text))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl UIElement for FileBrowser {
+    fn draw(&self) -> Result<(), std::io::Error> {
+        self.path.draw()?;
+        self.draw_entries()?;
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, code: KeyCode, modifiers: KeyModifiers, mode: TextMode) -> Result<DialogReturnValue, std::io::Error> {
+        if self.confirming {
+            let result = match code {
+                KeyCode::Char('y' | 'Y') => {
+                    self.confirming = false;
+                    self.overwrite = true;
+                    DialogReturnValue { submit: true, ..Default::default() }
+                },
+                KeyCode::Char('n' | 'N') | KeyCode::Esc => {
+                    self.confirming = false;
+                    DialogReturnValue::default()
+                },
+                _ => DialogReturnValue::default()
+            };
+            self.draw()?;
+            self.show_focus_indicator(mode)?;
+            return Ok(result);
+        }
+
+        let result = match (self.focus, code) {
+            (Part::Path, KeyCode::Tab) if !self.path.captures_input(code, modifiers) => {
+                self.focus = Part::Entries;
+                self.path.hide_focus_indicator()?;
+                DialogReturnValue::default()
+            },
+            (Part::Path, KeyCode::Enter) => {
+                match self.typed_dir() {
+                    Some(dir) => self.open(dir),
+                    None => self.confirming = self.needs_confirmation()
+                }
+                DialogReturnValue::default()
+            },
+            (Part::Path, _) => {
+                let value = self.path_value();
+                let result = self.path.handle_input(code, modifiers, mode.clone())?;
+                if self.path_value() != value {
+                    self.overwrite = false;
+                }
+                // Typing a directory followed by a separator lists it straight away.
+                if let Some(dir) = self.typed_dir().filter(|d| *d != self.dir && self.path_value().ends_with(MAIN_SEPARATOR)) {
+                    self.dir = dir;
+                    self.cursor = 0;
+                    self.scroll = 0;
+                    self.load();
+                    self.draw_entries()?;
+                }
+                return Ok(result);
+            },
+            (Part::Entries, KeyCode::BackTab) => {
+                self.focus = Part::Path;
+                DialogReturnValue::default()
+            },
+            (Part::Entries, code) => {
+                match code {
+                    KeyCode::Up => self.select_entry(self.cursor.saturating_sub(1)),
+                    KeyCode::Down => self.select_entry(self.cursor + 1),
+                    KeyCode::PageUp => self.select_entry(self.cursor.saturating_sub(self.rows)),
+                    KeyCode::PageDown => self.select_entry(self.cursor + self.rows),
+                    KeyCode::Home => self.select_entry(0),
+                    KeyCode::End => self.select_entry(usize::MAX),
+                    KeyCode::Enter => self.open_entry(),
+                    KeyCode::Backspace => {
+                        if let Some(parent) = self.dir.parent().map(Path::to_path_buf) {
+                            self.open(parent);
+                        }
+                    },
+                    KeyCode::Char('.') => {
+                        self.show_hidden = !self.show_hidden;
+                        self.load();
+                    },
+                    _ => return Ok(DialogReturnValue::default())
+                }
+                DialogReturnValue::default()
+            }
+        };
+
+        self.draw()?;
+        self.show_focus_indicator(mode)?;
+
+        Ok(result)
+    }
+
+    fn captures_input(&self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        if self.confirming {
+            return true;
+        }
+        match self.focus {
+            Part::Path => self.path.captures_input(code, modifiers)
+                || code == KeyCode::Tab
+                || (code == KeyCode::Enter && self.mode != FileMode::Directory && self.typed_dir().is_some())
+                || (code == KeyCode::Enter && self.needs_confirmation()),
+            Part::Entries => code == KeyCode::BackTab
+                || (code == KeyCode::Enter && self.entries.get(self.cursor).is_some_and(|e| e.is_dir))
+        }
+    }
+
+    fn show_focus_indicator(&self, mode: TextMode) -> Result<(), std::io::Error> {
+        if self.confirming {
+            stdout()
+                .queue(Hide)?
+                .queue(MoveTo(self.position.x as u16, (self.position.y + 2) as u16))?;
+            return Ok(());
+        }
+        match self.focus {
+            Part::Path => self.path.show_focus_indicator(mode),
+            Part::Entries => {
+                stdout()
+                    .queue(Hide)?
+                    .queue(MoveTo(self.position.x as u16, (self.position.y + 2 + self.cursor - self.scroll) as u16))?;
+                Ok(())
+            }
+        }
+    }
+
+    fn hide_focus_indicator(&mut self) -> Result<(), std::io::Error> {
+        self.path.hide_focus_indicator()?;
+        // Typing usually continues at the end of the path.
+        self.path.move_cursor_to_end();
+        if self.focus == Part::Entries {
+            self.focus = Part::Path;
+            self.draw_entries()?;
+        }
+
+        Ok(())
+    }
+
+    fn get_position(&self) -> Position {
+        self.position.clone()
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.path.set_position(position.clone());
+        self.position = position;
+    }
+
+    fn get_tab_index(&self) -> Option<usize> {
+        self.tab_index
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_value(&self) -> Option<(String, String)> {
+        Some((self.name.clone(), self.path_value()))
+    }
+
+    fn set_value(&mut self, value: &str) {
+        let path = PathBuf::from(value);
+        match path.is_dir() {
+            true => self.open(path),
+            false => {
+                if let Some(parent) = path.parent().filter(|p| p.is_dir()) {
+                    self.open(parent.to_path_buf());
+                }
+                self.set_path(value);
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let value = self.path_value();
+        let path = Path::new(&value);
+
+        match self.mode {
+            FileMode::Open if !path.is_file() => Err(format!("{} is not a file", value)),
+            FileMode::Directory if !path.is_dir() => Err(format!("{} is not a directory", value)),
+            FileMode::Save if value.is_empty() || path.is_dir() => Err("Enter a file name".into()),
+            FileMode::Save if self.needs_confirmation() => Err(format!("{} exists, press Enter to overwrite it", value)),
+            _ => Ok(())
+        }
+    }
+}
+
+fn dir_string(dir: &Path) -> String {
+    let dir = dir.to_string_lossy().into_owned();
+    if dir.ends_with(MAIN_SEPARATOR) { dir } else { dir + MAIN_SEPARATOR_STR }
+}
+
+fn format_size(size: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "K", "M", "G", "T"];
+
+    let mut size = size as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    match unit {
+        0 => format!("{}{}", size, UNITS[0]),
+        _ => format!("{:.1}{}", size, UNITS[unit])
+    }
+}
+
+// Paths in the input's directory that start with its last component.
+fn complete_path(input: &str, directories_only: bool) -> Vec<String> {
+    let (dir, prefix) = match input.rfind(MAIN_SEPARATOR) {
+        Some(i) => (&input[..=i], &input[i + 1..]),
+        None => ("", input)
+    };
+
+    let Ok(read) = fs::read_dir(if dir.is_empty() { "." } else { dir }) else {
+        return Vec::new();
+    };
+
+    let mut paths = read
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let is_dir = fs::metadata(entry.path()).map(|m| m.is_dir()).unwrap_or(false);
+            let visible = !name.starts_with('.') || prefix.starts_with('.');
+
+            (visible && name.starts_with(prefix) && (is_dir || !directories_only))
+                .then(|| format!("{}{}{}", dir, name, if is_dir { MAIN_SEPARATOR_STR } else { "" }))
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+// `*` matches any run of characters and `?` a single one.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            },
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            },
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                },
+                None => return false
+            }
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_matches_wildcards() {
+        assert!(glob_match("*.toml", "Cargo.toml"));
+        assert!(glob_match("*.toml", ".toml"));
+        assert!(!glob_match("*.toml", "Cargo.lock"));
+        assert!(glob_match("?.rs", "a.rs"));
+        assert!(!glob_match("?.rs", "ab.rs"));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(!glob_match("a*b*c", "aXbYc_"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("", "a"));
+    }
+
+    #[test]
+    fn complete_path_lists_matching_entries() {
+        let dir = std::env::temp_dir().join(format!("dialog_complete_{}", std::process::id()));
+        fs::create_dir_all(dir.join("alpine")).unwrap();
+        fs::write(dir.join("alpha.txt"), "").unwrap();
+        fs::write(dir.join("beta"), "").unwrap();
+        fs::write(dir.join(".hidden"), "").unwrap();
+        let base = dir_string(&dir);
+
+        assert_eq!(complete_path(&format!("{}al", base), false), vec![format!("{}alpha.txt", base), format!("{}alpine{}", base, MAIN_SEPARATOR)]);
+        assert_eq!(complete_path(&format!("{}al", base), true), vec![format!("{}alpine{}", base, MAIN_SEPARATOR)]);
+        // Hidden entries only complete once a dot has been typed.
+        assert_eq!(complete_path(&base, false).len(), 3);
+        assert_eq!(complete_path(&format!("{}.h", base), false), vec![format!("{}.hidden", base)]);
+        assert!(complete_path(&format!("{}missing{}", base, MAIN_SEPARATOR), false).is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
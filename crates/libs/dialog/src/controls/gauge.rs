@@ -0,0 +1,104 @@
+use std::io::stdout;
+
+use crossterm::{cursor::MoveTo, event::{KeyCode, KeyModifiers}, style::{Print, SetColors}, QueueableCommand};
+
+use crate::{colors::GaugeColors, dialog::{DialogReturnValue, TextMode}, utils::Position};
+
+use super::UIElement;
+
+/// A progress bar showing a percentage between 0 and 100.
+#[derive(Debug, Clone)]
+pub struct Gauge {
+    name: String,
+    width: usize,
+    percent: usize,
+    index: usize,
+    position: Position,
+    colors: GaugeColors
+}
+
+impl Gauge {
+    pub fn new(name: impl Into<String>, width: usize, index: usize) -> Self {
+        Self {
+            name: name.into(),
+            width: width.max(5),
+            percent: 0,
+            index,
+            position: Position::default(),
+            colors: GaugeColors::default()
+        }
+    }
+
+    pub fn set_percent(mut self, percent: usize) -> Self {
+        self.percent = percent.min(100);
+
+        self
+    }
+
+    pub(crate) fn set_colors(&mut self, colors: GaugeColors) {
+        self.colors = colors;
+    }
+
+    pub(crate) fn get_field_index(&self) -> usize {
+        self.index
+    }
+
+    pub(crate) fn get_width(&self) -> usize {
+        self.width
+    }
+}
+
+impl UIElement for Gauge {
+    fn draw(&self) -> Result<(), std::io::Error> {
+        let filled = self.width * self.percent / 100;
+        let text = format!("{:^width$}", format!("{}%", self.percent), width = self.width);
+        let (bar, empty) = text.split_at(filled);
+
+        stdout()
+            .queue(MoveTo(self.position.x as u16, self.position.y as u16))?
+            .queue(SetColors(self.colors.bar))?
+            .queue(Print(bar))?
+            .queue(SetColors(self.colors.empty))?
+            .queue(Print(empty))?;
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, _code: KeyCode, _modifiers: KeyModifiers, _mode: TextMode) -> Result<DialogReturnValue, std::io::Error> {
+        Ok(DialogReturnValue::default())
+    }
+
+    fn show_focus_indicator(&self, _mode: TextMode) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+
+    fn hide_focus_indicator(&mut self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+
+    fn get_position(&self) -> Position {
+        self.position.clone()
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn get_tab_index(&self) -> Option<usize> {
+        None
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_value(&self) -> Option<(String, String)> {
+        Some((self.name.clone(), self.percent.to_string()))
+    }
+
+    fn set_value(&mut self, value: &str) {
+        if let Ok(percent) = value.trim().parse::<usize>() {
+            self.percent = percent.min(100);
+        }
+    }
+}
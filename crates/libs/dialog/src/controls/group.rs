@@ -0,0 +1,242 @@
+use std::io::stdout;
+
+use crossterm::{cursor::MoveTo, event::{KeyCode, KeyModifiers}, style::{Color, Colors, Print, SetColors}, QueueableCommand};
+
+use crate::{
+    borders::{BorderChars, BorderStyle, Borders},
+    dialog::{DialogReturnValue, TextMode},
+    layout::Layout,
+    utils::{Position, Size}
+};
+
+use super::{Control, UIElement};
+
+/// A titled frame around other controls, e.g. the "Address" section of a contact form.
+///
+/// The children are added to the dialog like any other control and referred to by field index
+/// in the group's layout, so they take focus and return values as usual.
+#[derive(Debug, Clone)]
+pub struct GroupBox {
+    title: String,
+    layout: Layout,
+    style: BorderStyle,
+    border_chars: BorderChars,
+    padding: Position,
+    index: usize,
+    clip: Option<(usize, usize)>,
+    position: Position,
+    size: Size,
+    colors: Colors
+}
+
+impl GroupBox {
+    pub fn new(title: impl Into<String>, layout: Layout, index: usize) -> Self {
+        Self {
+            title: title.into(),
+            layout,
+            style: BorderStyle::Single,
+            border_chars: border_chars(BorderStyle::Single),
+            padding: (1, 0).into(),
+            index,
+            clip: None,
+            position: Position::default(),
+            size: Size::default(),
+            colors: Colors::new(Color::White, Color::Black)
+        }
+    }
+
+    pub fn set_style(mut self, style: BorderStyle) -> Self {
+        self.style = style;
+        self.border_chars = border_chars(style);
+
+        self
+    }
+
+    /// Space between the frame and the children, one column on each side by default.
+    pub fn set_padding(mut self, padding: Position) -> Self {
+        self.padding = padding;
+
+        self
+    }
+
+    pub(crate) fn set_clip(&mut self, clip: Option<(usize, usize)>) {
+        self.clip = clip;
+    }
+
+    pub(crate) fn set_colors(&mut self, colors: Colors) {
+        self.colors = colors;
+    }
+
+    pub(crate) fn get_field_index(&self) -> usize {
+        self.index
+    }
+
+    pub(crate) fn get_layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    pub(crate) fn get_width(&self) -> usize {
+        self.size.width
+    }
+
+    pub(crate) fn get_height(&self) -> usize {
+        self.size.height
+    }
+
+    // Size of the frame around the children, never narrower than the title.
+    pub(crate) fn measure(&self, controls: &[Control]) -> Size {
+        let content = self.layout.measure(controls);
+        let title = if self.title.is_empty() { 0 } else { self.title.chars().count() + 2 };
+
+        (
+            (content.width + 2 * self.padding.x).max(title + 2) + 2,
+            content.height + 2 * self.padding.y + 2
+        ).into()
+    }
+
+    // Takes the area given by the parent layout and returns the area left for the children.
+    pub(crate) fn set_bounds(&mut self, position: Position, size: Size) -> (Position, Size) {
+        let inner = (
+            (position.x + 1 + self.padding.x, position.y + 1 + self.padding.y).into(),
+            (
+                size.width.saturating_sub(2 + 2 * self.padding.x),
+                size.height.saturating_sub(2 + 2 * self.padding.y)
+            ).into()
+        );
+        self.position = position;
+        self.size = size;
+
+        inner
+    }
+}
+
+fn border_chars(style: BorderStyle) -> BorderChars {
+    BorderChars::new(Borders::new(style, style, style, style, style))
+}
+
+impl UIElement for GroupBox {
+    fn draw(&self) -> Result<(), std::io::Error> {
+        if self.size.width < 2 || self.size.height < 2 {
+            return Ok(());
+        }
+
+        let chars = &self.border_chars;
+        let x = self.position.x as u16;
+        let inner = self.size.width - 2;
+        let (skip, count) = self.clip.unwrap_or((0, self.size.height));
+
+        stdout().queue(SetColors(self.colors))?;
+        for row in skip..(skip + count).min(self.size.height) {
+            let y = (self.position.y + row - skip) as u16;
+            if row == 0 {
+                stdout()
+                    .queue(MoveTo(x, y))?
+                    .queue(Print(format!("{}{}{}", chars.tl, chars.top.to_string().repeat(inner), chars.tr)))?;
+
+                if !self.title.is_empty() {
+                    let title: String = format!(" {} ", self.title).chars().take(inner.saturating_sub(1)).collect();
+                    stdout()
+                        .queue(MoveTo(x + 2, y))?
+                        .queue(Print(title))?;
+                }
+            } else if row == self.size.height - 1 {
+                stdout()
+                    .queue(MoveTo(x, y))?
+                    .queue(Print(format!("{}{}{}", chars.bl, chars.bottom.to_string().repeat(inner), chars.br)))?;
+            } else {
+                stdout()
+                    .queue(MoveTo(x, y))?
+                    .queue(Print(chars.left))?
+                    .queue(MoveTo(x + self.size.width as u16 - 1, y))?
+                    .queue(Print(chars.right))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, _code: KeyCode, _modifiers: KeyModifiers, _mode: TextMode) -> Result<DialogReturnValue, std::io::Error> {
+        Ok(DialogReturnValue::default())
+    }
+
+    fn show_focus_indicator(&self, _mode: TextMode) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+
+    fn hide_focus_indicator(&mut self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+
+    fn get_position(&self) -> Position {
+        self.position.clone()
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn get_tab_index(&self) -> Option<usize> {
+        None
+    }
+
+    fn get_name(&self) -> String {
+        String::new()
+    }
+
+    fn get_value(&self) -> Option<(String, String)> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controls::label::Label;
+
+    use super::*;
+
+    // A four column label as the only child, next to the group itself.
+    fn group(title: &str) -> (GroupBox, Vec<Control>) {
+        (GroupBox::new(title, Layout::control(0), 1), vec![Control::Label(Label::new("abcd", 0))])
+    }
+
+    #[test]
+    fn measure_adds_the_frame_and_padding() {
+        let (group, controls) = group("");
+        let size = group.measure(&controls);
+        assert_eq!((size.width, size.height), (4 + 2 + 2, 1 + 2));
+
+        let group = group.set_padding((2, 1).into());
+        let size = group.measure(&controls);
+        assert_eq!((size.width, size.height), (4 + 4 + 2, 1 + 2 + 2));
+    }
+
+    #[test]
+    fn measure_fits_the_title() {
+        let (group, controls) = group("A long title");
+        let size = group.measure(&controls);
+
+        // The title, a space either side of it and two columns of top border before and after.
+        assert_eq!(size.width, 12 + 2 + 2 + 2);
+        assert_eq!(size.height, 3);
+    }
+
+    #[test]
+    fn set_bounds_leaves_the_inside_to_the_children() {
+        let (group, _) = group("Address");
+        let mut group = group.set_padding((1, 1).into());
+        let (position, size) = group.set_bounds((5, 3).into(), (20usize, 6usize).into());
+
+        assert_eq!((position.x, position.y), (7, 5));
+        assert_eq!((size.width, size.height), (16, 2));
+        assert_eq!((group.get_width(), group.get_height()), (20, 6));
+    }
+
+    #[test]
+    fn set_bounds_never_underflows() {
+        let (group, _) = group("");
+        let mut group = group.set_padding((3, 3).into());
+        let (_, size) = group.set_bounds(Position::default(), (4usize, 4usize).into());
+
+        assert_eq!((size.width, size.height), (0, 0));
+    }
+}
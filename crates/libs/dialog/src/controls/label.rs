@@ -0,0 +1,160 @@
+use std::io::stdout;
+
+use crossterm::{cursor::MoveTo, event::{KeyCode, KeyModifiers}, style::{Color, Colors, Print, SetColors}, QueueableCommand};
+
+use crate::{dialog::{DialogReturnValue, TextMode}, utils::Position};
+
+use super::UIElement;
+
+#[derive(Debug, Clone)]
+pub struct Label {
+    name: String,
+    text: String,
+    wrap: Option<usize>,
+    lines: Vec<String>,
+    index: usize,
+    clip: Option<(usize, usize)>,
+    position: Position,
+    colors: Colors
+}
+
+impl Label {
+    pub fn new(text: impl Into<String>, index: usize) -> Self {
+        let text = text.into();
+
+        Self {
+            name: String::new(),
+            lines: text.lines().map(String::from).collect(),
+            text,
+            wrap: None,
+            index,
+            clip: None,
+            position: Position::default(),
+            colors: Colors::new(Color::White, Color::Black)
+        }
+    }
+
+    /// Word-wraps the text so no line is wider than `width`.
+    pub fn set_wrap(mut self, width: usize) -> Self {
+        self.wrap = Some(width.max(1));
+        self.lines = wrap(&self.text, width.max(1));
+
+        self
+    }
+
+    /// Names the label so its text can be replaced through `Dialog::set_value`.
+    pub fn set_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+
+        self
+    }
+
+    pub(crate) fn set_clip(&mut self, clip: Option<(usize, usize)>) {
+        self.clip = clip;
+    }
+
+    pub(crate) fn set_colors(&mut self, colors: Colors) {
+        self.colors = colors;
+    }
+
+    pub(crate) fn get_field_index(&self) -> usize {
+        self.index
+    }
+
+    pub(crate) fn get_width(&self) -> usize {
+        self.lines.iter().map(|l| l.chars().count()).max().unwrap_or(0)
+    }
+
+    pub(crate) fn get_height(&self) -> usize {
+        self.lines.len().max(1)
+    }
+}
+
+impl UIElement for Label {
+    fn draw(&self) -> Result<(), std::io::Error> {
+        stdout().queue(SetColors(self.colors))?;
+
+        let (skip, count) = self.clip.unwrap_or((0, self.lines.len()));
+        for (row, line) in self.lines.iter().skip(skip).take(count).enumerate() {
+            stdout()
+                .queue(MoveTo(self.position.x as u16, (self.position.y + row) as u16))?
+                .queue(Print(line))?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, _code: KeyCode, _modifiers: KeyModifiers, _mode: TextMode) -> Result<DialogReturnValue, std::io::Error> {
+        Ok(DialogReturnValue::default())
+    }
+
+    fn show_focus_indicator(&self, _mode: TextMode) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+
+    fn hide_focus_indicator(&mut self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+
+    fn get_position(&self) -> Position {
+        self.position.clone()
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn get_tab_index(&self) -> Option<usize> {
+        None
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_value(&self) -> Option<(String, String)> {
+        None
+    }
+
+    fn set_value(&mut self, value: &str) {
+        self.text = value.to_string();
+        self.lines = match self.wrap {
+            Some(width) => wrap(&self.text, width),
+            None => self.text.lines().map(String::from).collect()
+        };
+    }
+}
+
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    text.lines().flat_map(|paragraph| {
+        let mut lines = Vec::new();
+        let mut line = String::new();
+
+        for word in paragraph.split_whitespace() {
+            let mut word = word.to_string();
+
+            while word.chars().count() > width {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                let rest = word.split_off(word.char_indices().nth(width).map(|(i, _)| i).unwrap_or(word.len()));
+                lines.push(word);
+                word = rest;
+            }
+            if word.is_empty() {
+                continue;
+            }
+
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+
+        lines.push(line);
+        lines
+    }).collect()
+}
@@ -0,0 +1,268 @@
+use std::io::stdout;
+
+use crossterm::{cursor::{Hide, MoveTo}, event::{KeyCode, KeyModifiers}, style::{Print, SetColors}, QueueableCommand};
+
+use crate::{colors::ListColors, dialog::{DialogReturnValue, TextMode}, utils::Position};
+
+use super::UIElement;
+
+/// Separator between the tags of a multi-selection in `FormData`.
+pub const VALUE_SEPARATOR: char = '\n';
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum ListMode {
+    /// The value is the tag under the cursor.
+    #[default]
+    Menu,
+    /// Any number of items can be ticked with Space.
+    Check,
+    /// Exactly one item can be ticked with Space.
+    Radio
+}
+
+#[derive(Debug, Clone)]
+pub struct ListItem {
+    pub(crate) tag: String,
+    pub(crate) description: String,
+    pub(crate) selected: bool
+}
+
+impl ListItem {
+    pub fn new(tag: impl Into<String>, description: impl Into<String>, selected: bool) -> Self {
+        Self {
+            tag: tag.into(),
+            description: description.into(),
+            selected
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct List {
+    name: String,
+    tab_index: Option<usize>,
+    index: usize,
+    mode: ListMode,
+    items: Vec<ListItem>,
+    rows: usize,
+    full_rows: usize,
+    cursor: usize,
+    scroll: usize,
+    position: Position,
+    colors: ListColors
+}
+
+impl List {
+    /// A `rows` of 0 shows every item.
+    pub fn new(name: impl Into<String>, items: Vec<ListItem>, rows: usize, mode: ListMode, tab_index: Option<usize>, index: usize) -> Self {
+        let cursor = match mode {
+            ListMode::Menu => 0,
+            ListMode::Check | ListMode::Radio => items.iter().position(|i| i.selected).unwrap_or(0)
+        };
+
+        let mut list = Self {
+            name: name.into(),
+            tab_index,
+            index,
+            mode,
+            rows: if rows == 0 { items.len().max(1) } else { rows },
+            full_rows: if rows == 0 { items.len().max(1) } else { rows },
+            items,
+            cursor,
+            scroll: 0,
+            position: Position::default(),
+            colors: ListColors::default()
+        };
+        list.scroll_to_cursor();
+        list
+    }
+
+    /// A menu of `(tag, description)` pairs, its value is the tag of the highlighted item.
+    pub fn menu<T: Into<String>, D: Into<String>>(name: impl Into<String>, items: impl IntoIterator<Item = (T, D)>, rows: usize, tab_index: Option<usize>, index: usize) -> Self {
+        let items = items.into_iter().map(|(tag, description)| ListItem::new(tag, description, false)).collect();
+
+        Self::new(name, items, rows, ListMode::Menu, tab_index, index)
+    }
+
+    pub(crate) fn set_colors(&mut self, colors: ListColors) {
+        self.colors = colors;
+    }
+
+    pub(crate) fn get_field_index(&self) -> usize {
+        self.index
+    }
+
+    pub(crate) fn get_height(&self) -> usize {
+        self.rows
+    }
+
+    pub(crate) fn get_width(&self) -> usize {
+        self.marker_width() + self.tag_width() + 2 + self.description_width()
+    }
+
+    fn marker_width(&self) -> usize {
+        match self.mode {
+            ListMode::Menu => 0,
+            ListMode::Check | ListMode::Radio => 4
+        }
+    }
+
+    fn tag_width(&self) -> usize {
+        self.items.iter().map(|i| i.tag.chars().count()).max().unwrap_or(0)
+    }
+
+    fn description_width(&self) -> usize {
+        self.items.iter().map(|i| i.description.chars().count()).max().unwrap_or(0)
+    }
+
+    pub(crate) fn fit_height(&mut self, height: usize) {
+        self.rows = self.full_rows.min(height).max(1);
+        self.scroll_to_cursor();
+    }
+
+    fn scroll_to_cursor(&mut self) {
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + self.rows {
+            self.scroll = self.cursor + 1 - self.rows;
+        }
+    }
+
+    fn move_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.items.len().saturating_sub(1));
+        self.scroll_to_cursor();
+    }
+
+    // Jumps to the next item whose tag starts with `key`, wrapping around.
+    fn jump_to(&mut self, key: char) {
+        let key = key.to_lowercase().collect::<String>();
+        let len = self.items.len();
+
+        if let Some(cursor) = (1..=len)
+            .map(|i| (self.cursor + i) % len)
+            .find(|i| self.items[*i].tag.to_lowercase().starts_with(&key)) {
+            self.move_cursor(cursor);
+        }
+    }
+
+    fn toggle(&mut self) {
+        match self.mode {
+            ListMode::Menu => {},
+            ListMode::Check => {
+                if let Some(item) = self.items.get_mut(self.cursor) {
+                    item.selected = !item.selected;
+                }
+            },
+            ListMode::Radio => {
+                let cursor = self.cursor;
+                self.items.iter_mut().enumerate().for_each(|(i, item)| item.selected = i == cursor);
+            }
+        }
+    }
+
+    fn format_item(&self, item: &ListItem) -> String {
+        let marker = match (&self.mode, item.selected) {
+            (ListMode::Menu, _) => "",
+            (ListMode::Check, true) => "[*] ",
+            (ListMode::Check, false) => "[ ] ",
+            (ListMode::Radio, true) => "(*) ",
+            (ListMode::Radio, false) => "( ) "
+        };
+
+        format!("{}{:<tag_width$}  {:<description_width$}", marker, item.tag, item.description,
+            tag_width = self.tag_width(), description_width = self.description_width())
+    }
+}
+
+impl UIElement for List {
+    fn draw(&self) -> Result<(), std::io::Error> {
+        let width = self.get_width();
+
+        for row in 0..self.rows {
+            let index = self.scroll + row;
+            let (colors, text) = match self.items.get(index) {
+                Some(item) if index == self.cursor => (self.colors.selected, self.format_item(item)),
+                Some(item) => (self.colors.item, self.format_item(item)),
+                None => (self.colors.item, String::new())
+            };
+
+            stdout()
+                .queue(SetColors(colors))?
+                .queue(MoveTo(self.position.x as u16, (self.position.y + row) as u16))?
+                .queue(Print(format!("{:<width$}", text, width = width)))?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, code: KeyCode, _modifiers: KeyModifiers, _mode: TextMode) -> Result<DialogReturnValue, std::io::Error> {
+        match code {
+            KeyCode::Up => self.move_cursor(self.cursor.saturating_sub(1)),
+            KeyCode::Down => self.move_cursor(self.cursor + 1),
+            KeyCode::PageUp => self.move_cursor(self.cursor.saturating_sub(self.rows)),
+            KeyCode::PageDown => self.move_cursor(self.cursor + self.rows),
+            KeyCode::Home => self.move_cursor(0),
+            KeyCode::End => self.move_cursor(self.items.len()),
+            KeyCode::Char(' ') => self.toggle(),
+            KeyCode::Char(c) => self.jump_to(c),
+            _ => return Ok(DialogReturnValue::default())
+        }
+        self.draw()?;
+
+        Ok(DialogReturnValue::default())
+    }
+
+    fn show_focus_indicator(&self, _mode: TextMode) -> Result<(), std::io::Error> {
+        stdout()
+            .queue(Hide)?
+            .queue(MoveTo(self.position.x as u16, (self.position.y + self.cursor - self.scroll) as u16))?;
+
+        Ok(())
+    }
+
+    fn hide_focus_indicator(&mut self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+
+    fn get_position(&self) -> Position {
+        self.position.clone()
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn get_tab_index(&self) -> Option<usize> {
+        self.tab_index
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_value(&self) -> Option<(String, String)> {
+        let value = match self.mode {
+            ListMode::Menu => self.items.get(self.cursor).map(|i| i.tag.clone()).unwrap_or_default(),
+            ListMode::Check | ListMode::Radio => self.items
+                .iter()
+                .filter(|i| i.selected)
+                .map(|i| i.tag.as_str())
+                .collect::<Vec<_>>()
+                .join(&VALUE_SEPARATOR.to_string())
+        };
+
+        Some((self.name.clone(), value))
+    }
+
+    fn set_value(&mut self, value: &str) {
+        let tags = value.split(VALUE_SEPARATOR).collect::<Vec<_>>();
+
+        match self.mode {
+            ListMode::Menu => {},
+            ListMode::Check | ListMode::Radio => self.items.iter_mut().for_each(|i| i.selected = tags.contains(&i.tag.as_str()))
+        }
+        if let Some(cursor) = self.items.iter().position(|i| tags.contains(&i.tag.as_str())) {
+            self.move_cursor(cursor);
+        }
+    }
+}
@@ -4,19 +4,166 @@ use crate::{dialog::{DialogReturnValue, TextMode}, utils::Position};
 
 pub mod field;
 pub mod button;
+pub mod checkbox;
+pub mod label;
+pub mod list;
+pub mod gauge;
+pub mod tail;
+pub mod textbox;
+pub mod tree;
+pub mod table;
+pub mod buildlist;
+pub mod file_browser;
+pub mod group;
 
 
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Center,
+    Right
+}
+
+impl Alignment {
+    // Pads or truncates `text` to exactly `width` columns.
+    pub(crate) fn apply(&self, text: &str, width: usize) -> String {
+        let text = text.chars().take(width).collect::<String>();
+        match self {
+            Alignment::Left => format!("{:<width$}", text, width = width),
+            Alignment::Center => format!("{:^width$}", text, width = width),
+            Alignment::Right => format!("{:>width$}", text, width = width)
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum Control {
     TextField(field::Field),
-    Button(button::Button)
+    Button(button::Button),
+    Checkbox(checkbox::Checkbox),
+    Label(label::Label),
+    List(list::List),
+    Gauge(gauge::Gauge),
+    TextBox(textbox::TextBox),
+    Tree(tree::Tree),
+    Table(table::Table),
+    BuildList(buildlist::BuildList),
+    FileBrowser(file_browser::FileBrowser),
+    Group(group::GroupBox)
+}
+
+impl Control {
+    // Order of the control in the dialog body, `None` for controls placed in the button bar.
+    pub(crate) fn get_field_index(&self) -> Option<usize> {
+        match self {
+            Control::TextField(f) => Some(f.get_field_index()),
+            Control::Button(_) => None,
+            Control::Checkbox(c) => Some(c.get_field_index()),
+            Control::Label(l) => Some(l.get_field_index()),
+            Control::List(s) => Some(s.get_field_index()),
+            Control::Gauge(g) => Some(g.get_field_index()),
+            Control::Group(g) => Some(g.get_field_index()),
+            Control::TextBox(t) => Some(t.get_field_index()),
+            Control::Tree(t) => Some(t.get_field_index()),
+            Control::Table(t) => Some(t.get_field_index()),
+            Control::BuildList(b) => Some(b.get_field_index()),
+            Control::FileBrowser(f) => Some(f.get_field_index())
+        }
+    }
+
+    pub(crate) fn get_height(&self) -> usize {
+        match self {
+            Control::TextField(_) | Control::Button(_) | Control::Checkbox(_) | Control::Gauge(_) => 1,
+            Control::Label(l) => l.get_height(),
+            Control::List(s) => s.get_height(),
+            Control::TextBox(t) => t.get_height(),
+            Control::Tree(t) => t.get_height(),
+            Control::Table(t) => t.get_height(),
+            Control::BuildList(b) => b.get_height(),
+            Control::FileBrowser(f) => f.get_height(),
+            Control::Group(g) => g.get_height()
+        }
+    }
+
+    // Full width of the control, including any label drawn to its left.
+    pub(crate) fn get_width(&self) -> usize {
+        match self {
+            Control::TextField(f) => f.get_label_width() + f.get_display_window(),
+            Control::Button(b) => b.name.len(),
+            Control::Checkbox(c) => c.get_label_width() + 3,
+            Control::Label(l) => l.get_width(),
+            Control::List(s) => s.get_width(),
+            Control::Gauge(g) => g.get_width(),
+            Control::Group(g) => g.get_width(),
+            Control::TextBox(t) => t.get_width(),
+            Control::Tree(t) => t.get_width(),
+            Control::Table(t) => t.get_width(),
+            Control::BuildList(b) => b.get_width(),
+            Control::FileBrowser(f) => f.get_width()
+        }
+    }
+
+    // Shows fewer rows of a scrolling control when the dialog is too short for all of them. `height`
+    // is the room in the body; controls grow back to the rows they were created with when it's taller.
+    pub(crate) fn fit_height(&mut self, height: usize) {
+        match self {
+            Control::List(l) => l.fit_height(height),
+            Control::TextBox(t) => t.fit_height(height),
+            Control::Tree(t) => t.fit_height(height),
+            Control::Table(t) => t.fit_height(height),
+            Control::BuildList(b) => b.fit_height(height),
+            Control::FileBrowser(f) => f.fit_height(height),
+            _ => {}
+        }
+    }
+
+    // Limits drawing to `count` rows starting `skip` rows into the control, drawn from its position.
+    // Returns false for controls that can only be drawn whole.
+    pub(crate) fn set_clip(&mut self, clip: Option<(usize, usize)>) -> bool {
+        match self {
+            Control::Label(l) => l.set_clip(clip),
+            Control::Group(g) => g.set_clip(clip),
+            _ => return false
+        }
+
+        true
+    }
+
+    // Width of the "Name: " prefix for controls whose label is aligned with their neighbours'.
+    pub(crate) fn get_label_width(&self) -> Option<usize> {
+        match self {
+            Control::TextField(f) => Some(f.get_label_width()),
+            Control::Checkbox(c) => Some(c.get_label_width()),
+            _ => None
+        }
+    }
+
+    // Text drawn on the dialog's bottom split line, e.g. the scroll position of a text box.
+    pub(crate) fn get_status(&self) -> Option<String> {
+        match self {
+            Control::TextBox(t) => Some(t.get_status()),
+            _ => None
+        }
+    }
 }
 
 impl UIElement for Control {
     fn draw(&self) -> Result<(), std::io::Error> {
         match self {
             Control::TextField(f) => f.draw()?,
-            Control::Button(b) => b.draw()?
+            Control::Button(b) => b.draw()?,
+            Control::Checkbox(c) => c.draw()?,
+            Control::Label(l) => l.draw()?,
+            Control::List(s) => s.draw()?,
+            Control::Gauge(g) => g.draw()?,
+            Control::Group(g) => g.draw()?,
+            Control::TextBox(t) => t.draw()?,
+            Control::Tree(t) => t.draw()?,
+            Control::Table(t) => t.draw()?,
+            Control::BuildList(b) => b.draw()?,
+            Control::FileBrowser(f) => f.draw()?
         };
         
         Ok(())
@@ -25,14 +172,34 @@ impl UIElement for Control {
     fn handle_input(&mut self, code: KeyCode, modifiers: KeyModifiers, mode: TextMode) -> Result<DialogReturnValue, std::io::Error> {
         match self {
             Control::TextField(f) => f.handle_input(code, modifiers, mode),
-            Control::Button(b) => b.handle_input(code, modifiers, mode)
+            Control::Button(b) => b.handle_input(code, modifiers, mode),
+            Control::Checkbox(c) => c.handle_input(code, modifiers, mode),
+            Control::Label(l) => l.handle_input(code, modifiers, mode),
+            Control::List(s) => s.handle_input(code, modifiers, mode),
+            Control::Gauge(g) => g.handle_input(code, modifiers, mode),
+            Control::Group(g) => g.handle_input(code, modifiers, mode),
+            Control::TextBox(t) => t.handle_input(code, modifiers, mode),
+            Control::Tree(t) => t.handle_input(code, modifiers, mode),
+            Control::Table(t) => t.handle_input(code, modifiers, mode),
+            Control::BuildList(b) => b.handle_input(code, modifiers, mode),
+            Control::FileBrowser(f) => f.handle_input(code, modifiers, mode)
         }
     }
 
     fn show_focus_indicator(&self, mode: TextMode) -> Result<(), std::io::Error> {
         match self {
             Control::TextField(f) => f.show_focus_indicator(mode)?,
-            Control::Button(b) => b.show_focus_indicator(mode)?
+            Control::Button(b) => b.show_focus_indicator(mode)?,
+            Control::Checkbox(c) => c.show_focus_indicator(mode)?,
+            Control::Label(l) => l.show_focus_indicator(mode)?,
+            Control::List(s) => s.show_focus_indicator(mode)?,
+            Control::Gauge(g) => g.show_focus_indicator(mode)?,
+            Control::Group(g) => g.show_focus_indicator(mode)?,
+            Control::TextBox(t) => t.show_focus_indicator(mode)?,
+            Control::Tree(t) => t.show_focus_indicator(mode)?,
+            Control::Table(t) => t.show_focus_indicator(mode)?,
+            Control::BuildList(b) => b.show_focus_indicator(mode)?,
+            Control::FileBrowser(f) => f.show_focus_indicator(mode)?
         }
 
         Ok(())
@@ -41,37 +208,155 @@ impl UIElement for Control {
     fn hide_focus_indicator(&mut self) -> Result<(), std::io::Error> {
         match self {
             Control::TextField(f) => f.hide_focus_indicator()?,
-            Control::Button(b) => b.hide_focus_indicator()?
+            Control::Button(b) => b.hide_focus_indicator()?,
+            Control::Checkbox(c) => c.hide_focus_indicator()?,
+            Control::Label(l) => l.hide_focus_indicator()?,
+            Control::List(s) => s.hide_focus_indicator()?,
+            Control::Gauge(g) => g.hide_focus_indicator()?,
+            Control::Group(g) => g.hide_focus_indicator()?,
+            Control::TextBox(t) => t.hide_focus_indicator()?,
+            Control::Tree(t) => t.hide_focus_indicator()?,
+            Control::Table(t) => t.hide_focus_indicator()?,
+            Control::BuildList(b) => b.hide_focus_indicator()?,
+            Control::FileBrowser(f) => f.hide_focus_indicator()?
         };
 
         Ok(())
     }
 
+    fn get_position(&self) -> Position {
+        match self {
+            Control::TextField(f) => f.get_position(),
+            Control::Button(b) => b.get_position(),
+            Control::Checkbox(c) => c.get_position(),
+            Control::Label(l) => l.get_position(),
+            Control::List(s) => s.get_position(),
+            Control::Gauge(g) => g.get_position(),
+            Control::Group(g) => g.get_position(),
+            Control::TextBox(t) => t.get_position(),
+            Control::Tree(t) => t.get_position(),
+            Control::Table(t) => t.get_position(),
+            Control::BuildList(b) => b.get_position(),
+            Control::FileBrowser(f) => f.get_position()
+        }
+    }
+
     fn set_position(&mut self, position: Position) {
         match self {
             Control::TextField(f) => f.set_position(position),
-            Control::Button(b) => b.set_position(position)
+            Control::Button(b) => b.set_position(position),
+            Control::Checkbox(c) => c.set_position(position),
+            Control::Label(l) => l.set_position(position),
+            Control::List(s) => s.set_position(position),
+            Control::Gauge(g) => g.set_position(position),
+            Control::Group(g) => g.set_position(position),
+            Control::TextBox(t) => t.set_position(position),
+            Control::Tree(t) => t.set_position(position),
+            Control::Table(t) => t.set_position(position),
+            Control::BuildList(b) => b.set_position(position),
+            Control::FileBrowser(f) => f.set_position(position)
         };
     }
 
+    fn captures_input(&self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        match self {
+            Control::TextField(f) => f.captures_input(code, modifiers),
+            Control::Button(b) => b.captures_input(code, modifiers),
+            Control::Checkbox(c) => c.captures_input(code, modifiers),
+            Control::Label(l) => l.captures_input(code, modifiers),
+            Control::List(s) => s.captures_input(code, modifiers),
+            Control::Gauge(g) => g.captures_input(code, modifiers),
+            Control::Group(g) => g.captures_input(code, modifiers),
+            Control::TextBox(t) => t.captures_input(code, modifiers),
+            Control::Tree(t) => t.captures_input(code, modifiers),
+            Control::Table(t) => t.captures_input(code, modifiers),
+            Control::BuildList(b) => b.captures_input(code, modifiers),
+            Control::FileBrowser(f) => f.captures_input(code, modifiers)
+        }
+    }
+
     fn get_tab_index(&self) -> Option<usize> {
         match self {
             Control::TextField(f) => f.get_tab_index(),
-            Control::Button(b) => b.get_tab_index()
+            Control::Button(b) => b.get_tab_index(),
+            Control::Checkbox(c) => c.get_tab_index(),
+            Control::Label(l) => l.get_tab_index(),
+            Control::List(s) => s.get_tab_index(),
+            Control::Gauge(g) => g.get_tab_index(),
+            Control::Group(g) => g.get_tab_index(),
+            Control::TextBox(t) => t.get_tab_index(),
+            Control::Tree(t) => t.get_tab_index(),
+            Control::Table(t) => t.get_tab_index(),
+            Control::BuildList(b) => b.get_tab_index(),
+            Control::FileBrowser(f) => f.get_tab_index()
         }
     }
 
     fn get_value(&self) -> Option<(String, String)> {
         match self {
             Control::TextField(f) => f.get_value(),
-            Control::Button(b) => b.get_value()
+            Control::Button(b) => b.get_value(),
+            Control::Checkbox(c) => c.get_value(),
+            Control::Label(l) => l.get_value(),
+            Control::List(s) => s.get_value(),
+            Control::Gauge(g) => g.get_value(),
+            Control::Group(g) => g.get_value(),
+            Control::TextBox(t) => t.get_value(),
+            Control::Tree(t) => t.get_value(),
+            Control::Table(t) => t.get_value(),
+            Control::BuildList(b) => b.get_value(),
+            Control::FileBrowser(f) => f.get_value()
         }
     }
 
     fn get_name(&self) -> String {
         match self {
             Control::TextField(f) => f.get_name(),
-            Control::Button(b) => b.get_name()
+            Control::Button(b) => b.get_name(),
+            Control::Checkbox(c) => c.get_name(),
+            Control::Label(l) => l.get_name(),
+            Control::List(s) => s.get_name(),
+            Control::Gauge(g) => g.get_name(),
+            Control::Group(g) => g.get_name(),
+            Control::TextBox(t) => t.get_name(),
+            Control::Tree(t) => t.get_name(),
+            Control::Table(t) => t.get_name(),
+            Control::BuildList(b) => b.get_name(),
+            Control::FileBrowser(f) => f.get_name()
+        }
+    }
+
+    fn set_value(&mut self, value: &str) {
+        match self {
+            Control::TextField(f) => f.set_value(value),
+            Control::Button(b) => b.set_value(value),
+            Control::Checkbox(c) => c.set_value(value),
+            Control::Label(l) => l.set_value(value),
+            Control::List(s) => s.set_value(value),
+            Control::Gauge(g) => g.set_value(value),
+            Control::Group(g) => g.set_value(value),
+            Control::TextBox(t) => t.set_value(value),
+            Control::Tree(t) => t.set_value(value),
+            Control::Table(t) => t.set_value(value),
+            Control::BuildList(b) => b.set_value(value),
+            Control::FileBrowser(f) => f.set_value(value)
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            Control::TextField(f) => f.validate(),
+            Control::Button(b) => b.validate(),
+            Control::Checkbox(c) => c.validate(),
+            Control::Label(l) => l.validate(),
+            Control::List(s) => s.validate(),
+            Control::Gauge(g) => g.validate(),
+            Control::Group(g) => g.validate(),
+            Control::TextBox(t) => t.validate(),
+            Control::Tree(t) => t.validate(),
+            Control::Table(t) => t.validate(),
+            Control::BuildList(b) => b.validate(),
+            Control::FileBrowser(f) => f.validate()
         }
     }
 }
@@ -79,10 +364,15 @@ impl UIElement for Control {
 pub(crate) trait UIElement {
     fn draw(&self) -> Result<(), std::io::Error>;
     fn handle_input(&mut self, code: KeyCode, modifiers: KeyModifiers, text_mode: TextMode) -> Result<DialogReturnValue, std::io::Error>;
+    // Keys the control wants to see before the dialog applies its own bindings (Enter, Esc, Tab...).
+    fn captures_input(&self, _code: KeyCode, _modifiers: KeyModifiers) -> bool { false }
     fn show_focus_indicator(&self, mode: TextMode) -> Result<(), std::io::Error>;
     fn hide_focus_indicator(&mut self) -> Result<(), std::io::Error>;
+    fn get_position(&self) -> Position;
     fn set_position(&mut self, position: Position);
     fn get_tab_index(&self) -> Option<usize>;
     fn get_name(&self) -> String;
     fn get_value(&self) -> Option<(String, String)>;
+    fn set_value(&mut self, _value: &str) {}
+    fn validate(&self) -> Result<(), String> { Ok(()) }
 }
\ No newline at end of file
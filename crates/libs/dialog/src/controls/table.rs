@@ -0,0 +1,395 @@
+use std::{cmp::Ordering, io::stdout};
+
+use crossterm::{cursor::{Hide, MoveTo}, event::{KeyCode, KeyModifiers}, style::{Print, SetColors}, QueueableCommand};
+
+use crate::{colors::TableColors, dialog::{DialogReturnValue, TextMode}, utils::Position};
+
+use super::{list::{ListMode, VALUE_SEPARATOR}, Alignment, UIElement};
+
+const COLUMN_GAP: usize = 1;
+
+#[derive(Debug, Clone)]
+pub struct Column {
+    title: String,
+    width: usize,
+    alignment: Alignment
+}
+
+impl Column {
+    pub fn new(title: impl Into<String>, width: usize) -> Self {
+        Self {
+            title: title.into(),
+            width: width.max(1),
+            alignment: Alignment::default()
+        }
+    }
+
+    pub fn set_alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TableRow {
+    id: String,
+    cells: Vec<String>,
+    selected: bool
+}
+
+impl TableRow {
+    pub fn new<C: Into<String>>(id: impl Into<String>, cells: impl IntoIterator<Item = C>) -> Self {
+        Self {
+            id: id.into(),
+            cells: cells.into_iter().map(Into::into).collect(),
+            selected: false
+        }
+    }
+
+    pub fn set_selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+
+        self
+    }
+}
+
+/// Rows of cells under a header. Selection works like `List`: the value is the id of the row under the cursor for
+/// `ListMode::Menu`, or the ids of the ticked rows otherwise.
+///
+/// Keys 1-9 sort by that column, pressing the same key again reverses the order. Left/Right scroll by column.
+#[derive(Debug, Clone)]
+pub struct Table {
+    name: String,
+    tab_index: Option<usize>,
+    index: usize,
+    mode: ListMode,
+    columns: Vec<Column>,
+    rows: Vec<TableRow>,
+    height: usize,
+    full_height: usize,
+    width: Option<usize>,
+    cursor: usize,
+    scroll: usize,
+    left: usize,
+    sort: Option<(usize, bool)>,
+    position: Position,
+    colors: TableColors
+}
+
+impl Table {
+    /// `height` is the number of visible rows, not counting the header.
+    pub fn new(name: impl Into<String>, columns: Vec<Column>, rows: Vec<TableRow>, height: usize, mode: ListMode, tab_index: Option<usize>, index: usize) -> Self {
+        Self {
+            name: name.into(),
+            tab_index,
+            index,
+            mode,
+            columns,
+            rows,
+            height: height.max(1),
+            full_height: height.max(1),
+            width: None,
+            cursor: 0,
+            scroll: 0,
+            left: 0,
+            sort: None,
+            position: Position::default(),
+            colors: TableColors::default()
+        }
+    }
+
+    /// Limits the visible width, the columns scroll horizontally if they don't fit.
+    pub fn set_width(mut self, width: usize) -> Self {
+        self.width = Some(width.max(1));
+
+        self
+    }
+
+    pub(crate) fn set_colors(&mut self, colors: TableColors) {
+        self.colors = colors;
+    }
+
+    pub(crate) fn get_field_index(&self) -> usize {
+        self.index
+    }
+
+    pub(crate) fn get_width(&self) -> usize {
+        self.width.unwrap_or_else(|| self.marker_width() + self.columns_width(0))
+    }
+
+    pub(crate) fn get_height(&self) -> usize {
+        self.height + 1
+    }
+
+    fn marker_width(&self) -> usize {
+        match self.mode {
+            ListMode::Menu => 0,
+            ListMode::Check | ListMode::Radio => 4
+        }
+    }
+
+    fn columns_width(&self, from: usize) -> usize {
+        self.columns.iter().skip(from).map(|c| c.width + COLUMN_GAP).sum::<usize>().saturating_sub(COLUMN_GAP)
+    }
+
+    pub(crate) fn fit_height(&mut self, height: usize) {
+        self.height = self.full_height.min(height.saturating_sub(1)).max(1);
+        self.move_cursor(self.cursor);
+    }
+
+    fn move_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.rows.len().saturating_sub(1));
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + self.height {
+            self.scroll = self.cursor + 1 - self.height;
+        }
+    }
+
+    fn scroll_horizontally(&mut self, left: usize) {
+        // No point scrolling once the remaining columns fit, or past the last one when it's wider than the table.
+        let max_left = (0..self.columns.len())
+            .find(|i| self.marker_width() + self.columns_width(*i) <= self.get_width())
+            .unwrap_or(self.columns.len().saturating_sub(1));
+        self.left = left.min(max_left);
+    }
+
+    fn sort_by(&mut self, column: usize) {
+        if column >= self.columns.len() {
+            return;
+        }
+
+        let ascending = !matches!(self.sort, Some((c, true)) if c == column);
+        let id = self.rows.get(self.cursor).map(|r| r.id.clone());
+
+        self.rows.sort_by(|a, b| {
+            let ordering = compare(a.cells.get(column), b.cells.get(column));
+            if ascending { ordering } else { ordering.reverse() }
+        });
+        self.sort = Some((column, ascending));
+
+        // Keep the cursor on the same row.
+        if let Some(cursor) = id.and_then(|id| self.rows.iter().position(|r| r.id == id)) {
+            self.move_cursor(cursor);
+        }
+    }
+
+    fn toggle(&mut self) {
+        match self.mode {
+            ListMode::Menu => {},
+            ListMode::Check => {
+                if let Some(row) = self.rows.get_mut(self.cursor) {
+                    row.selected = !row.selected;
+                }
+            },
+            ListMode::Radio => {
+                let cursor = self.cursor;
+                self.rows.iter_mut().enumerate().for_each(|(i, row)| row.selected = i == cursor);
+            }
+        }
+    }
+
+    fn format_cells<'a>(&self, cells: impl Fn(usize) -> &'a str, marker: &str) -> String {
+        let line = self.columns
+            .iter()
+            .enumerate()
+            .skip(self.left)
+            .map(|(i, column)| column.alignment.apply(cells(i), column.width))
+            .collect::<Vec<_>>()
+            .join(&" ".repeat(COLUMN_GAP));
+
+        Alignment::Left.apply(&format!("{}{}", marker, line), self.get_width())
+    }
+
+    fn format_header(&self) -> String {
+        let titles = self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| match self.sort {
+                Some((sorted, ascending)) if sorted == i => format!("{}{}", column.title, if ascending { '▲' } else { '▼' }),
+                _ => column.title.clone()
+            })
+            .collect::<Vec<_>>();
+
+        self.format_cells(|i| titles[i].as_str(), &" ".repeat(self.marker_width()))
+    }
+
+    fn format_row(&self, row: &TableRow) -> String {
+        let marker = match (&self.mode, row.selected) {
+            (ListMode::Menu, _) => "",
+            (ListMode::Check, true) => "[*] ",
+            (ListMode::Check, false) => "[ ] ",
+            (ListMode::Radio, true) => "(*) ",
+            (ListMode::Radio, false) => "( ) "
+        };
+
+        self.format_cells(|i| row.cells.get(i).map(String::as_str).unwrap_or(""), marker)
+    }
+}
+
+// Numbers sort numerically, everything else case-insensitively.
+fn compare(a: Option<&String>, b: Option<&String>) -> Ordering {
+    let (a, b) = (a.map(String::as_str).unwrap_or(""), b.map(String::as_str).unwrap_or(""));
+
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.to_lowercase().cmp(&b.to_lowercase())
+    }
+}
+
+impl UIElement for Table {
+    fn draw(&self) -> Result<(), std::io::Error> {
+        stdout()
+            .queue(SetColors(self.colors.header))?
+            .queue(MoveTo(self.position.x as u16, self.position.y as u16))?
+            .queue(Print(self.format_header()))?;
+
+        for line in 0..self.height {
+            let index = self.scroll + line;
+            let (colors, text) = match self.rows.get(index) {
+                Some(row) if index == self.cursor => (self.colors.selected, self.format_row(row)),
+                Some(row) => (self.colors.row, self.format_row(row)),
+                None => (self.colors.row, " ".repeat(self.get_width()))
+            };
+
+            stdout()
+                .queue(SetColors(colors))?
+                .queue(MoveTo(self.position.x as u16, (self.position.y + 1 + line) as u16))?
+                .queue(Print(text))?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, code: KeyCode, _modifiers: KeyModifiers, mode: TextMode) -> Result<DialogReturnValue, std::io::Error> {
+        match code {
+            KeyCode::Up => self.move_cursor(self.cursor.saturating_sub(1)),
+            KeyCode::Down => self.move_cursor(self.cursor + 1),
+            KeyCode::PageUp => self.move_cursor(self.cursor.saturating_sub(self.height)),
+            KeyCode::PageDown => self.move_cursor(self.cursor + self.height),
+            KeyCode::Home => self.move_cursor(0),
+            KeyCode::End => self.move_cursor(self.rows.len()),
+            KeyCode::Left => self.scroll_horizontally(self.left.saturating_sub(1)),
+            KeyCode::Right => self.scroll_horizontally(self.left + 1),
+            KeyCode::Char(' ') => self.toggle(),
+            KeyCode::Char(c @ '1'..='9') => self.sort_by(c as usize - '1' as usize),
+            _ => return Ok(DialogReturnValue::default())
+        }
+        self.draw()?;
+        self.show_focus_indicator(mode)?;
+
+        Ok(DialogReturnValue::default())
+    }
+
+    fn show_focus_indicator(&self, _mode: TextMode) -> Result<(), std::io::Error> {
+        stdout()
+            .queue(Hide)?
+            .queue(MoveTo(self.position.x as u16, (self.position.y + 1 + self.cursor - self.scroll) as u16))?;
+
+        Ok(())
+    }
+
+    fn hide_focus_indicator(&mut self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+
+    fn get_position(&self) -> Position {
+        self.position.clone()
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn get_tab_index(&self) -> Option<usize> {
+        self.tab_index
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_value(&self) -> Option<(String, String)> {
+        let value = match self.mode {
+            ListMode::Menu => self.rows.get(self.cursor).map(|r| r.id.clone()).unwrap_or_default(),
+            ListMode::Check | ListMode::Radio => self.rows
+                .iter()
+                .filter(|r| r.selected)
+                .map(|r| r.id.as_str())
+                .collect::<Vec<_>>()
+                .join(&VALUE_SEPARATOR.to_string())
+        };
+
+        Some((self.name.clone(), value))
+    }
+
+    fn set_value(&mut self, value: &str) {
+        let ids = value.split(VALUE_SEPARATOR).collect::<Vec<_>>();
+
+        if self.mode != ListMode::Menu {
+            self.rows.iter_mut().for_each(|r| r.selected = ids.contains(&r.id.as_str()));
+        }
+        if let Some(cursor) = self.rows.iter().position(|r| ids.contains(&r.id.as_str())) {
+            self.move_cursor(cursor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(columns: &[usize], rows: &[(&str, &str)]) -> Table {
+        let columns = columns.iter().enumerate().map(|(i, width)| Column::new(format!("C{}", i), *width)).collect();
+        let rows = rows.iter().map(|(id, cell)| TableRow::new(*id, [*cell])).collect();
+        Table::new("table", columns, rows, 5, ListMode::Menu, None, 0)
+    }
+
+    fn ids(table: &Table) -> Vec<&str> {
+        table.rows.iter().map(|row| row.id.as_str()).collect()
+    }
+
+    fn cell(text: &str) -> Option<String> {
+        Some(text.to_string())
+    }
+
+    #[test]
+    fn compare_sorts_numbers_by_value_and_text_ignoring_case() {
+        assert_eq!(compare(cell("9").as_ref(), cell("10").as_ref()), Ordering::Less);
+        assert_eq!(compare(cell(" 2.5 ").as_ref(), cell("2.25").as_ref()), Ordering::Greater);
+        assert_eq!(compare(cell("apple").as_ref(), cell("Banana").as_ref()), Ordering::Less);
+        assert_eq!(compare(cell("10").as_ref(), cell("abc").as_ref()), Ordering::Less);
+        assert_eq!(compare(None, cell("a").as_ref()), Ordering::Less);
+        assert_eq!(compare(cell("Same").as_ref(), cell("same").as_ref()), Ordering::Equal);
+    }
+
+    #[test]
+    fn sort_by_toggles_direction_and_keeps_the_cursor_on_its_row() {
+        let mut table = table(&[5], &[("a", "3"), ("b", "1"), ("c", "2")]);
+
+        table.sort_by(0);
+        assert_eq!(ids(&table), ["b", "c", "a"]);
+        assert_eq!(table.cursor, 2);
+
+        table.sort_by(0);
+        assert_eq!(ids(&table), ["a", "c", "b"]);
+        assert_eq!(table.cursor, 0);
+
+        // Columns that don't exist are ignored.
+        table.sort_by(3);
+        assert_eq!(ids(&table), ["a", "c", "b"]);
+    }
+
+    #[test]
+    fn scrolls_until_the_remaining_columns_fit() {
+        let mut narrow = table(&[5, 5, 5], &[]).set_width(11);
+        narrow.scroll_horizontally(5);
+        assert_eq!(narrow.left, 1);
+
+        // A last column wider than the table can still be scrolled to.
+        let mut wide = table(&[5, 30], &[]).set_width(10);
+        wide.scroll_horizontally(5);
+        assert_eq!(wide.left, 1);
+    }
+}
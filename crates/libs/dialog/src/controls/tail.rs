@@ -0,0 +1,185 @@
+use std::{fs::File, io::{Read, Seek, SeekFrom}, path::PathBuf, rc::Rc, sync::mpsc::Receiver};
+
+/// Where a following `TextBox` gets new lines from.
+#[derive(Debug, Clone)]
+pub struct TailSource {
+    kind: SourceKind
+}
+
+#[derive(Debug, Clone)]
+enum SourceKind {
+    // `partial` holds the bytes after the last newline, which may end part way through a character.
+    File { path: PathBuf, offset: u64, partial: Vec<u8> },
+    // `Receiver` is not `Clone`, but `try_recv` only needs a shared reference.
+    Channel(Rc<Receiver<String>>)
+}
+
+// Files are read this much at a time, keeping only the lines a text box would show.
+const CHUNK_LEN: usize = 64 * 1024;
+// Longer lines are broken up, so a file without newlines can't grow `partial` without limit.
+const MAX_LINE_LEN: usize = 64 * 1024;
+
+// Lines read since the last call.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct TailLines {
+    // The source started over, e.g. a file was truncated, so earlier lines are stale.
+    pub(crate) reset: bool,
+    pub(crate) lines: Vec<String>
+}
+
+impl TailSource {
+    /// Follows a file like `tail -f`, starting with its current contents. A file that shrinks is shown again from the start.
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        Self {
+            kind: SourceKind::File { path: path.into(), offset: 0, partial: Vec::new() }
+        }
+    }
+
+    /// Takes every line sent on the channel; each message may hold several lines.
+    pub fn channel(receiver: Receiver<String>) -> Self {
+        Self {
+            kind: SourceKind::Channel(Rc::new(receiver))
+        }
+    }
+
+    /// Complete lines that arrived since the last call, at most the last `max_lines` of them.
+    pub(crate) fn read(&mut self, max_lines: usize) -> Result<TailLines, std::io::Error> {
+        let mut read = TailLines::default();
+
+        match &mut self.kind {
+            SourceKind::File { path, offset, partial } => {
+                let mut file = match File::open(&*path) {
+                    Ok(file) => file,
+                    // The file may not have been created yet, or is being rotated.
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(read),
+                    Err(e) => return Err(e)
+                };
+
+                let len = file.metadata()?.len();
+                if len < *offset {
+                    *offset = 0;
+                    partial.clear();
+                    read.reset = true;
+                }
+                if len == *offset {
+                    return Ok(read);
+                }
+
+                file.seek(SeekFrom::Start(*offset))?;
+                let mut chunk = vec![0; CHUNK_LEN];
+                loop {
+                    let count = file.read(&mut chunk)?;
+                    if count == 0 {
+                        break;
+                    }
+                    *offset += count as u64;
+                    partial.extend_from_slice(&chunk[..count]);
+                    take_lines(partial, &mut read.lines);
+                    keep_last(&mut read.lines, max_lines);
+                }
+            },
+            SourceKind::Channel(receiver) => {
+                read.lines = receiver
+                    .try_iter()
+                    .flat_map(|message| message.lines().map(String::from).collect::<Vec<_>>())
+                    .collect();
+                keep_last(&mut read.lines, max_lines);
+            }
+        }
+
+        Ok(read)
+    }
+}
+
+// Moves the complete lines out of `partial`. Only complete lines are decoded, so a character
+// split between reads stays intact.
+fn take_lines(partial: &mut Vec<u8>, lines: &mut Vec<String>) {
+    if let Some(end) = partial.iter().rposition(|b| *b == b'\n') {
+        let rest = partial.split_off(end + 1);
+        lines.extend(partial[..end].split(|b| *b == b'\n').map(|line| String::from_utf8_lossy(line).into_owned()));
+        *partial = rest;
+    }
+
+    while partial.len() > MAX_LINE_LEN {
+        // Steps back over continuation bytes so the break doesn't fall inside a character.
+        let end = (MAX_LINE_LEN - 3..=MAX_LINE_LEN).rev().find(|i| partial[*i] & 0xC0 != 0x80).unwrap_or(MAX_LINE_LEN);
+        let rest = partial.split_off(end);
+        lines.push(String::from_utf8_lossy(partial).into_owned());
+        *partial = rest;
+    }
+}
+
+fn keep_last(lines: &mut Vec<String>, max_lines: usize) {
+    let excess = lines.len().saturating_sub(max_lines);
+    lines.drain(..excess);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::OpenOptions, io::Write};
+
+    use super::*;
+
+    #[test]
+    fn file_keeps_characters_split_between_reads() {
+        let path = std::env::temp_dir().join(format!("dialog_tail_{}", std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        let mut source = TailSource::file(&path);
+
+        // "é" is 0xC3 0xA9, written one byte at a time.
+        file.write_all(b"one\ncaf\xC3").unwrap();
+        assert_eq!(source.read(10).unwrap().lines, vec!["one"]);
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"\xA9\ntwo").unwrap();
+        assert_eq!(source.read(10).unwrap().lines, vec!["café"]);
+
+        file.write_all(b"\n").unwrap();
+        assert_eq!(source.read(10).unwrap().lines, vec!["two"]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn file_keeps_only_the_last_lines() {
+        let path = std::env::temp_dir().join(format!("dialog_tail_last_{}", std::process::id()));
+        let text = (0..100_000).map(|i| format!("line {}\n", i)).collect::<String>();
+        std::fs::write(&path, text).unwrap();
+        let mut source = TailSource::file(&path);
+
+        assert_eq!(source.read(2).unwrap().lines, vec!["line 99998", "line 99999"]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn file_breaks_up_lines_without_newlines() {
+        let path = std::env::temp_dir().join(format!("dialog_tail_long_{}", std::process::id()));
+        // Two-byte characters, so the break has to step back to a character boundary.
+        std::fs::write(&path, "é".repeat(MAX_LINE_LEN)).unwrap();
+        let mut source = TailSource::file(&path);
+
+        let lines = source.read(usize::MAX).unwrap().lines;
+        assert!(!lines.is_empty());
+        assert!(lines.iter().all(|line| line.len() <= MAX_LINE_LEN && !line.contains('\u{FFFD}')));
+        match &source.kind {
+            SourceKind::File { partial, .. } => assert!(partial.len() <= MAX_LINE_LEN),
+            _ => unreachable!()
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn file_resets_when_truncated() {
+        let path = std::env::temp_dir().join(format!("dialog_tail_reset_{}", std::process::id()));
+        std::fs::write(&path, "one\ntwo\n").unwrap();
+        let mut source = TailSource::file(&path);
+        assert_eq!(source.read(10).unwrap(), TailLines { reset: false, lines: vec!["one".into(), "two".into()] });
+
+        std::fs::write(&path, "new\n").unwrap();
+        assert_eq!(source.read(10).unwrap(), TailLines { reset: true, lines: vec!["new".into()] });
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
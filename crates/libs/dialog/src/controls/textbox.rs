@@ -0,0 +1,413 @@
+use std::io::stdout;
+
+use crossterm::{cursor::{Hide, MoveTo, Show}, event::{KeyCode, KeyModifiers}, style::{Print, SetColors}, QueueableCommand};
+
+use crate::{colors::TextBoxColors, dialog::{DialogReturnValue, TextMode}, utils::Position};
+
+use super::{tail::TailSource, UIElement};
+
+const TAB_WIDTH: usize = 8;
+const DEFAULT_MAX_LINES: usize = 10_000;
+
+/// A read-only, scrollable view of a block of text, e.g. a license or a log file.
+///
+/// `/` starts a search, `n` jumps to the next match. With a `TailSource` the box follows new lines as they
+/// arrive (see `Dialog::update`), unless the user has scrolled away from the bottom.
+#[derive(Debug, Clone)]
+pub struct TextBox {
+    name: String,
+    tab_index: Option<usize>,
+    index: usize,
+    lines: Vec<String>,
+    width: usize,
+    rows: usize,
+    full_rows: usize,
+    top: usize,
+    left: usize,
+    search: Option<String>,
+    query: String,
+    found: Option<usize>,
+    tail: Option<TailSource>,
+    following: bool,
+    max_lines: usize,
+    position: Position,
+    colors: TextBoxColors
+}
+
+impl TextBox {
+    pub fn new(name: impl Into<String>, text: &str, width: usize, rows: usize, tab_index: Option<usize>, index: usize) -> Self {
+        Self {
+            name: name.into(),
+            tab_index,
+            index,
+            lines: split_lines(text),
+            width: width.max(1),
+            rows: rows.max(1),
+            full_rows: rows.max(1),
+            top: 0,
+            left: 0,
+            search: None,
+            query: String::new(),
+            found: None,
+            tail: None,
+            following: false,
+            max_lines: DEFAULT_MAX_LINES,
+            position: Position::default(),
+            colors: TextBoxColors::default()
+        }
+    }
+
+    /// Appends lines from `source` whenever the dialog is updated, scrolling along with them.
+    pub fn set_tail(mut self, source: TailSource) -> Self {
+        self.tail = Some(source);
+        self.following = true;
+
+        self
+    }
+
+    /// Oldest lines are dropped once there are more than `max_lines`.
+    pub fn set_max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = max_lines.max(1);
+        self.trim();
+
+        self
+    }
+
+    /// Reads pending lines from the tail source, returns `true` if any arrived.
+    pub(crate) fn update(&mut self) -> Result<bool, std::io::Error> {
+        let Some(tail) = &mut self.tail else {
+            return Ok(false);
+        };
+
+        let read = tail.read(self.max_lines)?;
+        if !read.reset && read.lines.is_empty() {
+            return Ok(false);
+        }
+
+        if read.reset {
+            self.lines.clear();
+            self.top = 0;
+            self.found = None;
+        }
+        self.lines.extend(read.lines.iter().map(|line| expand_tabs(line)));
+        self.trim();
+        if self.following {
+            self.top = self.max_top();
+        }
+
+        Ok(true)
+    }
+
+    fn trim(&mut self) {
+        let excess = self.lines.len().saturating_sub(self.max_lines);
+        if excess == 0 {
+            return;
+        }
+
+        self.lines.drain(..excess);
+        self.top = self.top.saturating_sub(excess);
+        self.found = self.found.and_then(|found| found.checked_sub(excess));
+    }
+
+    pub(crate) fn set_colors(&mut self, colors: TextBoxColors) {
+        self.colors = colors;
+    }
+
+    pub(crate) fn get_field_index(&self) -> usize {
+        self.index
+    }
+
+    pub(crate) fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub(crate) fn get_height(&self) -> usize {
+        self.rows
+    }
+
+    /// How far through the text the bottom of the view is.
+    pub(crate) fn get_status(&self) -> String {
+        let percent = match self.lines.len() {
+            len if len <= self.rows => 100,
+            len => (self.top + self.rows).min(len) * 100 / len
+        };
+
+        format!(" {}% ", percent)
+    }
+
+    pub(crate) fn fit_height(&mut self, height: usize) {
+        self.rows = self.full_rows.min(height).max(1);
+        self.top = if self.following { self.max_top() } else { self.top.min(self.max_top()) };
+    }
+
+    fn max_top(&self) -> usize {
+        self.lines.len().saturating_sub(self.rows)
+    }
+
+    fn scroll_to(&mut self, top: usize) {
+        self.top = top.min(self.max_top());
+        // Scrolling back pauses following, returning to the bottom resumes it.
+        self.following = self.tail.is_some() && self.top == self.max_top();
+    }
+
+    fn scroll_horizontally(&mut self, left: usize) {
+        let longest = self.lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        self.left = left.min(longest.saturating_sub(self.width));
+    }
+
+    // Searches forward from `from`, wrapping around at the end of the text.
+    fn find_next(&mut self, from: usize) -> bool {
+        if self.query.is_empty() || self.lines.is_empty() {
+            return false;
+        }
+
+        let len = self.lines.len();
+        let found = (0..len)
+            .map(|i| (from + i) % len)
+            .find_map(|i| self.lines[i].find(&self.query).map(|byte| (i, self.lines[i][..byte].chars().count())));
+
+        match found {
+            Some((line, column)) => {
+                self.found = Some(line);
+                self.scroll_to(line);
+                if column < self.left || column + self.query.chars().count() > self.left + self.width {
+                    self.scroll_horizontally(column);
+                }
+                true
+            },
+            None => false
+        }
+    }
+
+    fn handle_search_input(&mut self, code: KeyCode) -> Result<(), std::io::Error> {
+        let Some(search) = &mut self.search else {
+            return Ok(());
+        };
+
+        match code {
+            KeyCode::Char(c) => search.push(c),
+            KeyCode::Backspace => { search.pop(); },
+            KeyCode::Esc => self.search = None,
+            KeyCode::Enter => {
+                self.query = self.search.take().unwrap_or_default();
+                if !self.find_next(self.top) {
+                    stdout().queue(Print('\u{7}'))?;
+                }
+            },
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn prompt_position(&self) -> Position {
+        (self.position.x, self.position.y + self.rows - 1).into()
+    }
+}
+
+impl UIElement for TextBox {
+    fn draw(&self) -> Result<(), std::io::Error> {
+        for row in 0..self.rows {
+            let index = self.top + row;
+            let text = self.lines.get(index).map(|l| l.chars().skip(self.left).take(self.width).collect::<String>()).unwrap_or_default();
+            let colors = if self.found == Some(index) { self.colors.highlight } else { self.colors.text };
+
+            stdout()
+                .queue(SetColors(colors))?
+                .queue(MoveTo(self.position.x as u16, (self.position.y + row) as u16))?
+                .queue(Print(format!("{:<width$}", text, width = self.width)))?;
+        }
+
+        if let Some(search) = &self.search {
+            let prompt = format!("/{}", search);
+            let prompt = prompt.chars().skip(prompt.chars().count().saturating_sub(self.width)).collect::<String>();
+            let pos = self.prompt_position();
+
+            stdout()
+                .queue(SetColors(self.colors.highlight))?
+                .queue(MoveTo(pos.x as u16, pos.y as u16))?
+                .queue(Print(format!("{:<width$}", prompt, width = self.width)))?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, code: KeyCode, _modifiers: KeyModifiers, mode: TextMode) -> Result<DialogReturnValue, std::io::Error> {
+        if self.search.is_some() {
+            self.handle_search_input(code)?;
+        } else {
+            match code {
+                KeyCode::Up => self.scroll_to(self.top.saturating_sub(1)),
+                KeyCode::Down => self.scroll_to(self.top + 1),
+                KeyCode::PageUp => self.scroll_to(self.top.saturating_sub(self.rows)),
+                KeyCode::PageDown | KeyCode::Char(' ') => self.scroll_to(self.top + self.rows),
+                KeyCode::Home => self.scroll_to(0),
+                KeyCode::End => self.scroll_to(self.max_top()),
+                KeyCode::Left => self.scroll_horizontally(self.left.saturating_sub(1)),
+                KeyCode::Right => self.scroll_horizontally(self.left + 1),
+                KeyCode::Char('/') => self.search = Some(String::new()),
+                KeyCode::Char('n') => {
+                    if !self.find_next(self.found.map(|i| i + 1).unwrap_or(self.top)) {
+                        stdout().queue(Print('\u{7}'))?;
+                    }
+                },
+                _ => return Ok(DialogReturnValue::default())
+            }
+        }
+        self.draw()?;
+        self.show_focus_indicator(mode)?;
+
+        Ok(DialogReturnValue::default())
+    }
+
+    // Everything goes to the search prompt while it is open, including Enter and Esc.
+    fn captures_input(&self, _code: KeyCode, _modifiers: KeyModifiers) -> bool {
+        self.search.is_some()
+    }
+
+    fn show_focus_indicator(&self, _mode: TextMode) -> Result<(), std::io::Error> {
+        match &self.search {
+            Some(search) => {
+                let pos = self.prompt_position();
+                let x = pos.x + (search.chars().count() + 1).min(self.width - 1);
+                stdout()
+                    .queue(MoveTo(x as u16, pos.y as u16))?
+                    .queue(Show)?;
+            },
+            None => {
+                stdout().queue(Hide)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn hide_focus_indicator(&mut self) -> Result<(), std::io::Error> {
+        if self.search.take().is_some() {
+            self.draw()?;
+        }
+
+        Ok(())
+    }
+
+    fn get_position(&self) -> Position {
+        self.position.clone()
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn get_tab_index(&self) -> Option<usize> {
+        self.tab_index
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_value(&self) -> Option<(String, String)> {
+        None
+    }
+
+    fn set_value(&mut self, value: &str) {
+        self.lines = split_lines(value);
+        self.trim();
+        self.found = None;
+        self.scroll_to(self.top);
+        self.scroll_horizontally(self.left);
+    }
+}
+
+fn split_lines(text: &str) -> Vec<String> {
+    text.lines().map(expand_tabs).collect()
+}
+
+// Tabs are expanded so every char takes up exactly one column.
+fn expand_tabs(line: &str) -> String {
+    let mut expanded = String::new();
+    for c in line.chars() {
+        match c {
+            '\t' => expanded.push_str(&" ".repeat(TAB_WIDTH - expanded.chars().count() % TAB_WIDTH)),
+            '\r' => {},
+            c => expanded.push(c)
+        }
+    }
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn textbox(text: &str) -> TextBox {
+        TextBox::new("text", text, 10, 2, None, 0)
+    }
+
+    fn search(textbox: &mut TextBox, query: &str) {
+        textbox.search = Some(String::new());
+        for c in query.chars() {
+            textbox.handle_search_input(KeyCode::Char(c)).unwrap();
+        }
+        textbox.handle_search_input(KeyCode::Enter).unwrap();
+    }
+
+    #[test]
+    fn search_scrolls_to_the_first_match_below_the_top() {
+        let mut textbox = textbox("alpha\nbeta\ngamma\ndelta\nbeta again");
+        search(&mut textbox, "bet");
+
+        assert_eq!((textbox.query.as_str(), textbox.found, textbox.top), ("bet", Some(1), 1));
+        assert!(textbox.search.is_none());
+    }
+
+    #[test]
+    fn search_can_be_edited_and_cancelled() {
+        let mut textbox = textbox("alpha\nbeta");
+        textbox.search = Some(String::new());
+        for code in [KeyCode::Char('x'), KeyCode::Backspace, KeyCode::Char('b'), KeyCode::Esc] {
+            textbox.handle_search_input(code).unwrap();
+        }
+
+        assert!(textbox.search.is_none());
+        assert_eq!((textbox.query.as_str(), textbox.found), ("", None));
+    }
+
+    #[test]
+    fn find_next_wraps_around() {
+        let mut textbox = textbox("beta\nalpha\nbeta\ngamma\ndelta");
+        textbox.query = "beta".to_string();
+
+        assert!(textbox.find_next(1));
+        assert_eq!((textbox.found, textbox.top), (Some(2), 2));
+        assert!(textbox.find_next(3));
+        assert_eq!((textbox.found, textbox.top), (Some(0), 0));
+
+        textbox.query = "omega".to_string();
+        assert!(!textbox.find_next(0));
+        assert_eq!(textbox.found, Some(0));
+    }
+
+    #[test]
+    fn find_next_scrolls_sideways_to_the_match() {
+        let mut plain = textbox("short\n0123456789abcdefghij needle and more");
+        plain.query = "needle".to_string();
+
+        assert!(plain.find_next(0));
+        assert_eq!((plain.found, plain.left), (Some(1), 21));
+
+        // The match is counted in chars, not bytes.
+        let mut accented = textbox("ééééééééééééé needle and more");
+        accented.query = "needle".to_string();
+        assert!(accented.find_next(0));
+        assert_eq!(accented.left, 14);
+    }
+
+    #[test]
+    fn expand_tabs_pads_to_the_next_stop() {
+        assert_eq!(expand_tabs("\tx"), " ".repeat(8) + "x");
+        assert_eq!(expand_tabs("abc\tx"), "abc     x");
+        assert_eq!(expand_tabs("12345678\tx"), "12345678        x");
+        assert_eq!(expand_tabs("é\tx\r"), "é       x");
+    }
+}
@@ -0,0 +1,500 @@
+use std::{fmt::Debug, io::stdout, rc::Rc};
+
+use crossterm::{cursor::{Hide, MoveTo}, event::{KeyCode, KeyModifiers}, style::{Print, SetColors}, QueueableCommand};
+
+use crate::{borders::TreeGuides, colors::ListColors, dialog::{DialogReturnValue, TextMode}, utils::Position};
+
+use super::{list::VALUE_SEPARATOR, UIElement};
+
+type Loader = Rc<dyn Fn(&str) -> Vec<TreeNode>>;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum TreeSelection {
+    /// The value is the id of the node under the cursor, Space expands and collapses it.
+    #[default]
+    Single,
+    /// Any number of nodes can be ticked with Space, so only Right and Left expand and collapse.
+    Multi
+}
+
+#[derive(Debug, Clone)]
+pub struct TreeNode {
+    id: String,
+    label: String,
+    // `None` until the children of a lazy node have been loaded.
+    children: Option<Vec<TreeNode>>,
+    expanded: bool,
+    selected: bool
+}
+
+impl TreeNode {
+    pub fn new(id: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            children: Some(Vec::new()),
+            expanded: false,
+            selected: false
+        }
+    }
+
+    pub fn add_child(mut self, child: TreeNode) -> Self {
+        self.children.get_or_insert_with(Vec::new).push(child);
+
+        self
+    }
+
+    /// The children are fetched from the tree's loader the first time the node is expanded.
+    pub fn set_lazy(mut self) -> Self {
+        self.children = None;
+
+        self
+    }
+
+    pub fn set_expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+
+        self
+    }
+
+    pub fn set_selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+
+        self
+    }
+
+    fn has_children(&self) -> bool {
+        self.children.as_ref().map(|c| !c.is_empty()).unwrap_or(true)
+    }
+}
+
+// A node as it appears on screen.
+struct Row {
+    path: Vec<usize>,
+    // For every ancestor below the root level, whether more siblings follow it.
+    guides: Vec<bool>,
+    last: bool
+}
+
+/// Right expands the node under the cursor or steps into it and Left collapses it or steps out to its parent, in
+/// either selection mode.
+#[derive(Clone)]
+pub struct Tree {
+    name: String,
+    tab_index: Option<usize>,
+    index: usize,
+    roots: Vec<TreeNode>,
+    selection: TreeSelection,
+    loader: Option<Loader>,
+    width: usize,
+    rows: usize,
+    full_rows: usize,
+    cursor: usize,
+    scroll: usize,
+    position: Position,
+    colors: ListColors,
+    guides: TreeGuides
+}
+
+impl Debug for Tree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tree")
+            .field("name", &self.name)
+            .field("roots", &self.roots)
+            .field("selection", &self.selection)
+            .field("cursor", &self.cursor)
+            .finish()
+    }
+}
+
+impl Tree {
+    pub fn new(name: impl Into<String>, roots: Vec<TreeNode>, width: usize, rows: usize, selection: TreeSelection, tab_index: Option<usize>, index: usize) -> Self {
+        Self {
+            name: name.into(),
+            tab_index,
+            index,
+            roots,
+            selection,
+            loader: None,
+            width: width.max(1),
+            rows: rows.max(1),
+            full_rows: rows.max(1),
+            cursor: 0,
+            scroll: 0,
+            position: Position::default(),
+            colors: ListColors::default(),
+            guides: TreeGuides::default()
+        }
+    }
+
+    /// Called with the id of a lazy node to fetch its children.
+    pub fn set_loader(mut self, loader: impl Fn(&str) -> Vec<TreeNode> + 'static) -> Self {
+        self.loader = Some(Rc::new(loader));
+
+        self
+    }
+
+    pub(crate) fn set_colors(&mut self, colors: ListColors, guides: TreeGuides) {
+        self.colors = colors;
+        self.guides = guides;
+    }
+
+    pub(crate) fn get_field_index(&self) -> usize {
+        self.index
+    }
+
+    pub(crate) fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub(crate) fn get_height(&self) -> usize {
+        self.rows
+    }
+
+    fn visible_rows(&self) -> Vec<Row> {
+        fn walk(nodes: &[TreeNode], path: &[usize], guides: &[bool], rows: &mut Vec<Row>) {
+            for (i, node) in nodes.iter().enumerate() {
+                let mut node_path = path.to_vec();
+                node_path.push(i);
+                let last = i + 1 == nodes.len();
+
+                rows.push(Row { path: node_path.clone(), guides: guides.to_vec(), last });
+
+                if let (true, Some(children)) = (node.expanded, &node.children) {
+                    let mut child_guides = guides.to_vec();
+                    if !path.is_empty() {
+                        child_guides.push(!last);
+                    }
+                    walk(children, &node_path, &child_guides, rows);
+                }
+            }
+        }
+
+        let mut rows = Vec::new();
+        walk(&self.roots, &[], &[], &mut rows);
+        rows
+    }
+
+    fn node(&self, path: &[usize]) -> Option<&TreeNode> {
+        let (first, rest) = path.split_first()?;
+        rest.iter().try_fold(self.roots.get(*first)?, |node, i| node.children.as_ref()?.get(*i))
+    }
+
+    fn node_mut(&mut self, path: &[usize]) -> Option<&mut TreeNode> {
+        let (first, rest) = path.split_first()?;
+        rest.iter().try_fold(self.roots.get_mut(*first)?, |node, i| node.children.as_mut()?.get_mut(*i))
+    }
+
+    fn cursor_path(&self) -> Option<Vec<usize>> {
+        self.visible_rows().into_iter().nth(self.cursor).map(|row| row.path)
+    }
+
+    pub(crate) fn fit_height(&mut self, height: usize) {
+        self.rows = self.full_rows.min(height).max(1);
+        self.move_cursor(self.cursor);
+    }
+
+    fn move_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.visible_rows().len().saturating_sub(1));
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + self.rows {
+            self.scroll = self.cursor + 1 - self.rows;
+        }
+    }
+
+    fn set_expanded(&mut self, expanded: bool) {
+        let Some(path) = self.cursor_path() else { return };
+        let loader = self.loader.clone();
+
+        if let Some(node) = self.node_mut(&path) {
+            if expanded && node.children.is_none() {
+                node.children = Some(loader.map(|load| load(&node.id)).unwrap_or_default());
+            }
+            node.expanded = expanded && node.has_children();
+        }
+        self.move_cursor(self.cursor);
+    }
+
+    fn expand_or_enter(&mut self) {
+        match self.cursor_path().and_then(|path| self.node(&path).map(|n| (n.expanded, n.has_children()))) {
+            Some((false, true)) => self.set_expanded(true),
+            Some((true, true)) => self.move_cursor(self.cursor + 1),
+            _ => {}
+        }
+    }
+
+    fn collapse_or_leave(&mut self) {
+        let Some(path) = self.cursor_path() else { return };
+
+        match self.node(&path) {
+            Some(node) if node.expanded => self.set_expanded(false),
+            _ if path.len() > 1 => {
+                let parent = &path[..path.len() - 1];
+                if let Some(cursor) = self.visible_rows().iter().position(|row| row.path == parent) {
+                    self.move_cursor(cursor);
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn toggle(&mut self) {
+        let Some(path) = self.cursor_path() else { return };
+
+        match self.selection {
+            TreeSelection::Single => {
+                let expanded = self.node(&path).map(|n| n.expanded).unwrap_or(false);
+                self.set_expanded(!expanded);
+            },
+            TreeSelection::Multi => if let Some(node) = self.node_mut(&path) {
+                node.selected = !node.selected;
+            }
+        }
+    }
+
+    fn format_row(&self, row: &Row) -> String {
+        let Some(node) = self.node(&row.path) else {
+            return String::new();
+        };
+
+        let mut text = String::new();
+        for more in &row.guides {
+            text.push(if *more { self.guides.vertical } else { ' ' });
+            text.push_str("  ");
+        }
+        if row.path.len() > 1 {
+            text.push(if row.last { self.guides.last } else { self.guides.branch });
+            text.push(self.guides.horizontal);
+            text.push(' ');
+        }
+
+        text.push_str(match (node.has_children(), node.expanded) {
+            (false, _) => "  ",
+            (true, false) => "+ ",
+            (true, true) => "- "
+        });
+        if self.selection == TreeSelection::Multi {
+            text.push_str(if node.selected { "[x] " } else { "[ ] " });
+        }
+        text.push_str(&node.label);
+
+        text.chars().take(self.width).collect()
+    }
+
+    fn selected_ids(&self) -> Vec<&str> {
+        fn walk<'a>(nodes: &'a [TreeNode], ids: &mut Vec<&'a str>) {
+            for node in nodes {
+                if node.selected {
+                    ids.push(&node.id);
+                }
+                if let Some(children) = &node.children {
+                    walk(children, ids);
+                }
+            }
+        }
+
+        let mut ids = Vec::new();
+        walk(&self.roots, &mut ids);
+        ids
+    }
+}
+
+impl UIElement for Tree {
+    fn draw(&self) -> Result<(), std::io::Error> {
+        let rows = self.visible_rows();
+
+        for row in 0..self.rows {
+            let index = self.scroll + row;
+            let (colors, text) = match rows.get(index) {
+                Some(r) if index == self.cursor => (self.colors.selected, self.format_row(r)),
+                Some(r) => (self.colors.item, self.format_row(r)),
+                None => (self.colors.item, String::new())
+            };
+
+            stdout()
+                .queue(SetColors(colors))?
+                .queue(MoveTo(self.position.x as u16, (self.position.y + row) as u16))?
+                .queue(Print(format!("{:<width$}", text, width = self.width)))?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_input(&mut self, code: KeyCode, _modifiers: KeyModifiers, _mode: TextMode) -> Result<DialogReturnValue, std::io::Error> {
+        match code {
+            KeyCode::Up => self.move_cursor(self.cursor.saturating_sub(1)),
+            KeyCode::Down => self.move_cursor(self.cursor + 1),
+            KeyCode::PageUp => self.move_cursor(self.cursor.saturating_sub(self.rows)),
+            KeyCode::PageDown => self.move_cursor(self.cursor + self.rows),
+            KeyCode::Home => self.move_cursor(0),
+            KeyCode::End => self.move_cursor(usize::MAX),
+            KeyCode::Right => self.expand_or_enter(),
+            KeyCode::Left => self.collapse_or_leave(),
+            KeyCode::Char(' ') => self.toggle(),
+            _ => return Ok(DialogReturnValue::default())
+        }
+        self.draw()?;
+        self.show_focus_indicator(TextMode::default())?;
+
+        Ok(DialogReturnValue::default())
+    }
+
+    fn show_focus_indicator(&self, _mode: TextMode) -> Result<(), std::io::Error> {
+        stdout()
+            .queue(Hide)?
+            .queue(MoveTo(self.position.x as u16, (self.position.y + self.cursor - self.scroll) as u16))?;
+
+        Ok(())
+    }
+
+    fn hide_focus_indicator(&mut self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+
+    fn get_position(&self) -> Position {
+        self.position.clone()
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn get_tab_index(&self) -> Option<usize> {
+        self.tab_index
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_value(&self) -> Option<(String, String)> {
+        let value = match self.selection {
+            TreeSelection::Single => self.cursor_path().and_then(|path| self.node(&path).map(|n| n.id.clone())).unwrap_or_default(),
+            TreeSelection::Multi => self.selected_ids().join(&VALUE_SEPARATOR.to_string())
+        };
+
+        Some((self.name.clone(), value))
+    }
+
+    fn set_value(&mut self, value: &str) {
+        let ids = value.split(VALUE_SEPARATOR).collect::<Vec<_>>();
+
+        if self.selection == TreeSelection::Multi {
+            fn walk(nodes: &mut [TreeNode], ids: &[&str]) {
+                for node in nodes {
+                    node.selected = ids.contains(&node.id.as_str());
+                    if let Some(children) = &mut node.children {
+                        walk(children, ids);
+                    }
+                }
+            }
+            walk(&mut self.roots, &ids);
+        }
+
+        let rows = self.visible_rows();
+        if let Some(cursor) = rows.iter().position(|row| self.node(&row.path).map(|n| ids.contains(&n.id.as_str())).unwrap_or(false)) {
+            self.move_cursor(cursor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    fn tree(selection: TreeSelection) -> Tree {
+        let roots = vec![
+            TreeNode::new("a", "A")
+                .add_child(TreeNode::new("a1", "A1").add_child(TreeNode::new("a1x", "A1x")).set_expanded(true))
+                .add_child(TreeNode::new("a2", "A2"))
+                .set_expanded(true),
+            TreeNode::new("b", "B").add_child(TreeNode::new("b1", "B1"))
+        ];
+
+        let mut tree = Tree::new("tree", roots, 20, 10, selection, None, 0);
+        tree.set_colors(ListColors::default(), TreeGuides { vertical: '|', branch: '+', last: '`', horizontal: '-' });
+        tree
+    }
+
+    fn lines(tree: &Tree) -> Vec<String> {
+        tree.visible_rows().iter().map(|row| tree.format_row(row)).collect()
+    }
+
+    #[test]
+    fn visible_rows_skip_collapsed_children() {
+        let paths = tree(TreeSelection::Single).visible_rows().into_iter().map(|row| row.path).collect::<Vec<_>>();
+        assert_eq!(paths, [vec![0], vec![0, 0], vec![0, 0, 0], vec![0, 1], vec![1]]);
+    }
+
+    #[test]
+    fn guides_connect_siblings() {
+        assert_eq!(lines(&tree(TreeSelection::Single)), [
+            "- A",
+            "+- - A1",
+            "|  `-   A1x",
+            "`-   A2",
+            "+ B"
+        ]);
+    }
+
+    #[test]
+    fn space_expands_in_single_mode() {
+        let mut tree = tree(TreeSelection::Single);
+        tree.move_cursor(4);
+        tree.toggle();
+
+        assert_eq!(lines(&tree)[4..], ["- B", "`-   B1"]);
+        assert_eq!(tree.get_value().unwrap().1, "b");
+    }
+
+    #[test]
+    fn space_ticks_and_arrows_expand_in_multi_mode() {
+        let mut tree = tree(TreeSelection::Multi);
+        tree.move_cursor(4);
+        tree.toggle();
+        assert_eq!(lines(&tree).len(), 5);
+
+        tree.expand_or_enter();
+        tree.expand_or_enter();
+        tree.toggle();
+        assert_eq!(lines(&tree)[4..], ["- [x] B", "`-   [x] B1"]);
+        assert_eq!(tree.get_value().unwrap().1, format!("b{}b1", VALUE_SEPARATOR));
+
+        tree.collapse_or_leave();
+        tree.collapse_or_leave();
+        assert_eq!(tree.cursor, 4);
+        assert_eq!(lines(&tree).len(), 5);
+    }
+
+    #[test]
+    fn lazy_children_load_once() {
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let mut tree = Tree::new("tree", vec![TreeNode::new("root", "Root").set_lazy()], 20, 10, TreeSelection::Single, None, 0)
+            .set_loader(move |id| {
+                counter.set(counter.get() + 1);
+                vec![TreeNode::new(format!("{}/child", id), "Child")]
+            });
+
+        tree.expand_or_enter();
+        tree.collapse_or_leave();
+        tree.expand_or_enter();
+
+        assert_eq!(calls.get(), 1);
+        assert_eq!(tree.visible_rows().len(), 2);
+        assert_eq!(tree.node(&[0, 0]).unwrap().id, "root/child");
+    }
+
+    #[test]
+    fn lazy_nodes_without_children_stay_collapsed() {
+        let mut tree = Tree::new("tree", vec![TreeNode::new("root", "Root").set_lazy()], 20, 10, TreeSelection::Single, None, 0)
+            .set_loader(|_| Vec::new());
+
+        assert_eq!(tree.format_row(&tree.visible_rows()[0]), "+ Root");
+        tree.expand_or_enter();
+        assert_eq!(tree.format_row(&tree.visible_rows()[0]), "  Root");
+    }
+}
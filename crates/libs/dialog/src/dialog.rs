@@ -1,37 +1,123 @@
-use std::{collections::HashMap, io::{stdout, Write}};
+use std::{collections::HashSet, fmt::Display, io::{stdout, Write}, str::FromStr};
 
 use crossterm::{
-    cursor::{MoveTo, SetCursorStyle},
+    cursor::{self, MoveTo, SetCursorStyle},
     event::{KeyCode, KeyModifiers},
     style::{Print, SetColors},
-    terminal::{size, Clear, ClearType},
+    terminal::{size, Clear, ClearType, ScrollUp},
     QueueableCommand
 };
 
 use crate::{
     borders::{BorderChars, Borders},
     colors::DialogColors,
-    controls::{Control, UIElement},
-    utils::{Position, Size}
+    error::Error,
+    controls::{list::VALUE_SEPARATOR, Control, UIElement},
+    history::History,
+    layout::Layout,
+    utils::{field_key, Position, Size}
 };
 
+/// How the buttons are placed along the bottom of a dialog. Buttons that don't fit on one row
+/// wrap onto the next, each row aligned the same way.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ButtonAlignment {
+    Left,
+    Center,
+    Right,
+    /// Spaced evenly from the left edge to the right one, a lone button is centred.
+    #[default]
+    Spread
+}
+
+/// A corner, edge or the middle of the area a dialog is placed in.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    #[default]
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight
+}
+
+/// Where a dialog is drawn. Whatever the placement, the dialog is kept inside the terminal.
 #[derive(Debug, Default, Clone)]
-pub enum ButtonCount {
+pub enum Placement {
+    /// In the middle of the terminal.
+    #[default]
+    Center,
+    /// Top left corner at the given column and row.
+    Absolute(Position),
+    /// Against a corner or edge of the terminal, moved inwards by the offset.
+    Anchored(Anchor, Position),
+    /// Against a corner or edge of another dialog, e.g. a confirmation centred over its form.
+    /// Usually created with `Placement::over`.
+    Relative { parent: Position, size: (usize, usize), anchor: Anchor, offset: Position },
+    /// On the line below the cursor, scrolling the terminal up if there isn't room, for
+    /// prompts that run in the normal screen rather than the alternate one.
+    Inline
+}
+
+impl Placement {
+    /// Placed relative to `parent`, which must have been shown so its position is known.
+    pub fn over(parent: &Dialog, anchor: Anchor, offset: Position) -> Self {
+        match (&parent.position, &parent.size) {
+            (Some(position), Some(size)) => Placement::Relative {
+                parent: position.clone(),
+                size: (size.width, size.height),
+                anchor,
+                offset
+            },
+            _ => Placement::Anchored(anchor, offset)
+        }
+    }
+}
+
+/// A dialog's width or height. `Auto` fits the content, the others override it.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Dimension {
     #[default]
-    One,
-    Two,
-    Three
+    Auto,
+    Fixed(usize),
+    /// Share of the terminal's width or height.
+    Percent(usize)
 }
 
+impl Dimension {
+    fn resolve(self, content: usize, available: usize) -> usize {
+        match self {
+            Dimension::Auto => content,
+            Dimension::Fixed(length) => length,
+            Dimension::Percent(percent) => available * percent.min(100) / 100
+        }
+    }
+}
+
+// Columns a button takes including its focus indicator, and the gap between buttons.
+const BUTTON_PADDING: usize = 4;
+const BUTTON_SPACING: usize = 1;
+
 #[derive(Debug)]
 struct DialogSpecs {
     position: Position,
     size: Size,
-    margin: Position,
-    max_name_len: usize
+    margin: Position
 }
 
 
+// A tab of a paged dialog and the field indexes of the controls on it.
+#[derive(Debug)]
+struct Page {
+    title: String,
+    layout: Layout,
+    indexes: Vec<usize>
+}
+
 #[derive(Debug)]
 pub struct Dialog {
     position: Option<Position>,
@@ -45,13 +131,32 @@ pub struct Dialog {
     fill: bool,
     border_chars: BorderChars,
     margin: Position,
+    layout: Layout,
+    pages: Vec<Page>,
+    page: usize,
+    // Width of the body content, controls would run past the border of a narrower dialog.
+    body_width: usize,
+    // Height of the body content and how far it is scrolled when it doesn't fit on screen.
+    body_height: usize,
+    scroll: usize,
+    // Rows taken by each body control relative to the top of the body, as (field index, row, height).
+    rows: Vec<(usize, usize, usize)>,
+    offscreen: Vec<usize>,
     is_visible: bool,
     min_width: usize,
     min_height: usize,
+    width: Dimension,
+    height: Dimension,
+    placement: Placement,
+    // Where the cursor was when an inline dialog was first shown, so redraws stay in place.
+    inline_origin: Option<Position>,
     focused: usize,
-    button_count: Option<ButtonCount>,
+    button_alignment: ButtonAlignment,
     mode: TextMode,
     colors: DialogColors,
+    history: Option<History>,
+    error: Option<String>,
+    status: Option<String>,
 
     submit_result: DialogResult,
     cancel_result: DialogResult
@@ -68,13 +173,28 @@ impl Default for Dialog {
             title: String::default(),
             controls: Vec::new(),
             colors: DialogColors::default(),
+            history: None,
+            error: None,
+            status: None,
             overlay: false,
             fill: true,
             border_chars: BorderChars::default(),
             margin: Position::default(),
+            layout: Layout::vertical([]),
+            pages: Vec::new(),
+            page: 0,
+            body_width: 0,
+            body_height: 0,
+            scroll: 0,
+            rows: Vec::new(),
+            offscreen: Vec::new(),
             min_height: 4,
             min_width: 2,
-            button_count: None,
+            width: Dimension::Auto,
+            height: Dimension::Auto,
+            placement: Placement::Center,
+            inline_origin: None,
+            button_alignment: ButtonAlignment::default(),
             submit_result: DialogResult::Ok,
             cancel_result: DialogResult::Cancel,
             is_visible: false,
@@ -88,28 +208,271 @@ impl Default for Dialog {
 impl Dialog {
 
     pub fn get_data(&self) -> FormData {
-        FormData::new(self.controls.clone().into_iter().filter_map(|control| {
+        FormData::new(self.controls.iter().filter_map(|control| {
             control.get_value()
-        }).collect::<HashMap<String, String>>())
+        }).collect::<Vec<(String, String)>>())
     }
 
-    fn max_name_len(&self) -> usize {
-        self.controls.iter().map(|c| if let Control::TextField(field) = c {
-            field.get_name().len()
-        } else {
-            0
-        }).max().unwrap_or(0)
+    /// Updates the value of the named control, repainting the dialog if it is visible.
+    /// Used to drive controls from outside the input loop, e.g. a gauge fed by a background task.
+    pub fn set_value(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        let mut resized = false;
+        if let Some(control) = self.controls.iter_mut().find(|c| c.get_name() == name) {
+            let size = (control.get_width(), control.get_height());
+            control.set_value(value);
+            resized = size != (control.get_width(), control.get_height());
+        }
+
+        // A label given a longer text needs a bigger dialog and everything below it moved down.
+        if resized {
+            self.fit_content();
+            if self.is_visible {
+                return self.show();
+            }
+        }
+
+        if self.is_visible {
+            self.draw_border()?;
+            self.draw_title()?;
+            self.draw_split()?;
+            self.draw_controls()?;
+            self.set_focus()?;
+            stdout().flush()?;
+        }
+
+        Ok(())
     }
 
-    fn resize(&mut self) -> Result<(), std::io::Error> {
-        self.calc_size()?;
+    /// Pulls new lines into text boxes that follow a `TailSource`, repainting them if anything arrived.
+    /// Call this regularly from the event loop, e.g. whenever polling for input times out.
+    pub fn update(&mut self) -> Result<(), Error> {
+        let hidden = self.hidden_indexes();
+        let mut changed = false;
+        for control in self.controls.iter_mut() {
+            let shown = !is_hidden(control, &hidden) && !is_hidden(control, &self.offscreen);
+            if let Control::TextBox(text_box) = control {
+                if text_box.update()? {
+                    changed = true;
+                    if self.is_visible && shown {
+                        text_box.draw()?;
+                    }
+                }
+            }
+        }
+
+        if changed && self.is_visible {
+            self.draw_split()?;
+            self.set_focus()?;
+        }
+
+        Ok(())
+    }
+
+    /// Text shown on the right of the split line, e.g. "Step 2 of 4". Takes precedence over the
+    /// status of controls such as text boxes.
+    pub fn set_status(&mut self, status: Option<String>) -> Result<(), Error> {
+        self.status = status;
+
+        if self.is_visible {
+            self.draw_split()?;
+            self.set_focus()?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn set_button_label(&mut self, result: &DialogResult, label: &str) {
+        self.controls.iter_mut().for_each(|c| if let Control::Button(button) = c {
+            if button.get_result() == result {
+                button.name = label.to_string();
+            }
+        });
+    }
+
+    pub(crate) fn get_min_size(&self) -> (usize, usize) {
+        (self.min_width, self.min_height)
+    }
+
+    pub(crate) fn set_min_size(&mut self, width: usize, height: usize) {
+        self.min_width = self.min_width.max(width);
+        self.min_height = self.min_height.max(height);
+    }
+
+    /// Index of the page currently shown by a dialog with tabs.
+    pub fn get_page(&self) -> usize {
+        self.page
+    }
+
+    /// Switches to another tab, focusing its first control.
+    pub fn set_page(&mut self, page: usize) -> Result<(), Error> {
+        if page == self.page || page >= self.pages.len() {
+            return Ok(());
+        }
+
+        if self.is_visible {
+            self.defocus()?;
+        }
+        self.page = page;
+        self.focused = self.tab_order().first().copied().unwrap_or(0);
+        self.scroll = 0;
+        self.set_control_positions();
+
+        if self.is_visible {
+            self.redraw()?;
+        }
+
+        Ok(())
+    }
+
+    /// Switches tabs when the tab strip is clicked. Mouse capture has to be enabled by the caller.
+    pub fn handle_click(&mut self, column: u16, row: u16) -> Result<DialogReturnValue, Error> {
+        let page = self.tab_bounds().into_iter().position(|(x, y, width)| {
+            row as usize == y && (x..x + width).contains(&(column as usize))
+        });
+
+        if let Some(page) = page {
+            self.set_page(page)?;
+        }
+
+        Ok(DialogReturnValue::default())
+    }
+
+    // Field indexes of the controls on pages other than the current one.
+    fn hidden_indexes(&self) -> Vec<usize> {
+        self.pages
+            .iter()
+            .enumerate()
+            .filter(|(page, _)| *page != self.page)
+            .flat_map(|(_, page)| page.indexes.iter().copied())
+            .collect()
+    }
+
+    // Tab indexes of the controls that can currently take focus, in order.
+    fn tab_order(&self) -> Vec<usize> {
+        let hidden = self.hidden_indexes();
+        let mut order = self.controls
+            .iter()
+            .filter(|c| !is_hidden(c, &hidden))
+            .filter_map(|c| c.get_tab_index())
+            .collect::<Vec<_>>();
+        order.sort();
+
+        order
+    }
+
+    // Position and width of each tab in the strip above the pages.
+    fn tab_bounds(&self) -> Vec<(usize, usize, usize)> {
+        let Some(specs) = self.get_dialog_specs() else {
+            return Vec::new();
+        };
+
+        let y = specs.position.y + 1 + specs.margin.y;
+        let mut x = specs.position.x + 1 + specs.margin.x;
+        self.pages.iter().map(|page| {
+            let width = page.title.chars().count() + 2;
+            let bounds = (x, y, width);
+            x += width + 1;
+            bounds
+        }).collect()
+    }
+
+    // Rows of the body visible between the title (or tab strip) and the split line.
+    fn viewport_height(&self) -> usize {
+        let strip = if self.pages.is_empty() { 0 } else { 2 };
+        self.size.as_ref().map(|size| size.height.saturating_sub(3 + self.button_rows().len().max(1) + 2 * self.margin.y + strip)).unwrap_or(0)
+    }
+
+    // Indexes of the buttons on each row of the button bar, wrapped to the dialog's width.
+    fn button_rows(&self) -> Vec<Vec<usize>> {
+        let width = self.size.as_ref().map(|size| size.width).unwrap_or(self.min_width);
+        wrap_buttons(&self.controls, width.saturating_sub(2 + 2 * self.margin.x))
+    }
+
+    // Scrolls the body so the focused control is entirely visible, repainting it if it moved.
+    fn scroll_into_view(&mut self) -> Result<(), std::io::Error> {
+        let hidden = self.hidden_indexes();
+        let focused = self.controls
+            .iter()
+            .filter(|c| !is_hidden(c, &hidden))
+            .find(|c| c.get_tab_index() == Some(self.focused))
+            .and_then(|c| c.get_field_index());
+        let Some((_, row, height)) = focused.and_then(|index| self.rows.iter().find(|(i, _, _)| *i == index).copied()) else {
+            return Ok(());
+        };
+
+        let scroll = scroll_to(row, height, self.scroll, self.viewport_height(), self.body_height);
+        if scroll != self.scroll {
+            self.scroll = scroll;
+            self.set_control_positions();
+
+            if self.is_visible {
+                self.draw_border()?;
+                self.draw_title()?;
+                self.draw_split()?;
+                self.draw_controls()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Track and thumb on the right border, showing which part of a scrolled body is in view.
+    fn draw_scrollbar(&self) -> Result<(), std::io::Error> {
+        let viewport = self.viewport_height();
+        let (Some(size), Some(pos)) = (&self.size, &self.position) else {
+            return Ok(());
+        };
+        let track = size.height.saturating_sub(3 + self.button_rows().len().max(1));
+        if self.body_height <= viewport || track == 0 {
+            return Ok(());
+        }
+
+        let thumb = (track * viewport / self.body_height).clamp(1, track);
+        let start = (track - thumb) * self.scroll / (self.body_height - viewport);
+        let x = (pos.x + size.width - 1) as u16;
+
+        stdout().queue(SetColors(self.colors.border))?;
+        for row in 0..track {
+            stdout()
+                .queue(MoveTo(x, (pos.y + 1 + row) as u16))?
+                .queue(Print(if (start..start + thumb).contains(&row) { '█' } else { '░' }))?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_tabs(&self) -> Result<(), std::io::Error> {
+        for (index, (page, (x, y, _))) in self.pages.iter().zip(self.tab_bounds()).enumerate() {
+            let colors = if index == self.page { self.colors.tabs.active } else { self.colors.tabs.tab };
+            stdout()
+                .queue(SetColors(colors))?
+                .queue(MoveTo(x as u16, y as u16))?
+                .queue(Print(format!(" {} ", page.title)))?;
+        }
+
+        Ok(())
+    }
+
+    // Grows the dialog to fit controls that have grown since it was built.
+    fn fit_content(&mut self) {
+        let (content, _) = measure_body(&self.layout, &self.pages, &self.controls);
+        self.body_width = self.body_width.max(content.width);
+        self.min_width = self.min_width.max(content.width + 2 * self.margin.x + 2);
+        self.min_height = self.min_height.max(content.height + 2 * self.margin.y + 4);
+    }
+
+    fn resize(&mut self) -> Result<(), Error> {
         self.calc_screen_size()?;
+        self.calc_size()?;
+        if let Placement::Inline = self.placement {
+            self.calc_inline_origin()?;
+        }
         self.calc_pos();
 
         Ok(())
     }
     
-    pub fn show(&mut self) -> Result<(), std::io::Error> {
+    pub fn show(&mut self) -> Result<(), Error> {
         self.resize()?;
         self.is_visible = true;
         
@@ -118,7 +481,7 @@ impl Dialog {
         Ok(())
     }
 
-    pub fn hide(&mut self) -> Result<(), std::io::Error> {
+    pub fn hide(&mut self) -> Result<(), Error> {
         self.is_visible = false;
         self.draw()?;
 
@@ -127,7 +490,7 @@ impl Dialog {
     
     fn get_dialog_specs(&self) -> Option<DialogSpecs> {
         if let (Some(size), Some(position)) = (&self.size, &self.position) {
-            Some(DialogSpecs { position: position.clone(), size: size.clone(), margin: self.margin.clone(), max_name_len: self.max_name_len() })
+            Some(DialogSpecs { position: position.clone(), size: size.clone(), margin: self.margin.clone() })
         }
         else {
             None
@@ -206,12 +569,14 @@ impl Dialog {
         // endregion: -- Fill
         }
 
+        self.draw_scrollbar()?;
+
         Ok(())
     }
 
     fn draw_split(&self) -> Result<(), std::io::Error> {
         if let (Some(size), Some(pos)) = (&self.size, &self.position) {
-            let y = pos.y + size.height - 3;
+            let y = (pos.y + size.height).saturating_sub(2 + self.button_rows().len().max(1));
             
             stdout()
                 .queue(SetColors(self.colors.border))?
@@ -225,15 +590,36 @@ impl Dialog {
 
             stdout()
                 .queue(Print(self.border_chars.right_intersect))?;
+
+            if let Some(error) = &self.error {
+                let error: String = format!(" {} ", error).chars().take(size.width.saturating_sub(4)).collect();
+                stdout()
+                    .queue(MoveTo((pos.x + 2) as u16, y as u16))?
+                    .queue(Print(error))?;
+            }
+
+            let hidden = self.hidden_indexes();
+            let status = self.status.as_ref().map(|status| format!(" {} ", status))
+                .or_else(|| self.controls.iter().filter(|c| !is_hidden(c, &hidden)).find_map(|c| c.get_status()));
+            if let Some(status) = status {
+                let x = (pos.x + size.width).saturating_sub(status.chars().count() + 2);
+                stdout()
+                    .queue(MoveTo(x as u16, y as u16))?
+                    .queue(Print(status))?;
+            }
         }
         Ok(())
     }
 
     fn draw_controls(&self) -> Result<(), std::io::Error> {
         if let (Some(_), Some(_)) = (&self.size, &self.position) {
+            self.draw_tabs()?;
+
+            let hidden = self.hidden_indexes();
             self
                 .controls
                 .iter()
+                .filter(|c| !is_hidden(c, &hidden) && !is_hidden(c, &self.offscreen))
                 .try_for_each(|control| control.draw())?;
         }
 
@@ -259,23 +645,22 @@ impl Dialog {
     }
 
     fn get_focused_control(&mut self) -> Option<&mut Control> {
+        let hidden = self.hidden_indexes();
         self
             .controls
             .iter_mut()
-            .filter(|c| c.get_tab_index().is_some())
+            .filter(|c| c.get_tab_index().is_some() && !is_hidden(c, &hidden))
             .find(|c| c.get_tab_index() == Some(self.focused))
     }
 
     fn set_focus(&mut self) -> Result<(), std::io::Error> {
         let mode = self.mode.clone();
-        let mut control = self.get_focused_control();
-        
-        if control.is_none() {
-            self.focused = 0;
-            control = self.get_focused_control();
+        if self.get_focused_control().is_none() {
+            self.focused = self.tab_order().first().copied().unwrap_or(0);
         }
-         
-        if let Some(control) = control {
+        self.scroll_into_view()?;
+
+        if let Some(control) = self.get_focused_control() {
             control.show_focus_indicator(mode)?;
             stdout().flush()?;
         }
@@ -283,19 +668,16 @@ impl Dialog {
         Ok(())
     }
     
-    fn focus_last(&mut self) -> Result<(), std::io::Error> {        
+    fn focus_last(&mut self) -> Result<(), std::io::Error> {
         let mode = self.mode.clone();
-        self
-            .controls
-            .iter_mut()
-            .filter_map(|control| control
-                .get_tab_index()
-                .map(|tab_index| (control, tab_index)))
-            .max_by_key(|(_, tab_index)| *tab_index)
-            .map(|(control, _)| {
-                self.focused = control.get_tab_index().unwrap_or(0);
-                control.show_focus_indicator(mode)
-            }).transpose()?;
+        if let Some(last) = self.tab_order().last() {
+            self.focused = *last;
+        }
+        self.scroll_into_view()?;
+
+        if let Some(control) = self.get_focused_control() {
+            control.show_focus_indicator(mode)?;
+        }
 
         Ok(())
     }
@@ -313,15 +695,11 @@ impl Dialog {
         let mode = self.mode.clone();
         self.defocus()?;
 
-        self.focused = self.focused.saturating_add(1);
-        let mut control = self.get_focused_control();
+        let order = self.tab_order();
+        self.focused = order.iter().find(|i| **i > self.focused).or(order.first()).copied().unwrap_or(0);
+        self.scroll_into_view()?;
 
-        if control.is_none() {
-            self.focused = 0;
-            control = self.get_focused_control();            
-        }
-
-        if let Some(control) = control {
+        if let Some(control) = self.get_focused_control() {
             control.show_focus_indicator(mode)?;
             stdout().flush()?;
         }
@@ -333,13 +711,14 @@ impl Dialog {
         let mode = self.mode.clone();
         self.defocus()?;
 
-        if self.focused == 0 {
+        let Some(previous) = self.tab_order().into_iter().rev().find(|i| *i < self.focused) else {
             self.focus_last()?;
             stdout().flush()?;
             return Ok(())
-        }
+        };
 
-        self.focused -= 1;
+        self.focused = previous;
+        self.scroll_into_view()?;
 
         if let Some(control) = self.get_focused_control() {
             control.show_focus_indicator(mode)?;
@@ -361,19 +740,42 @@ impl Dialog {
             Ok(())
     }
 
-    pub fn handle_input(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<DialogReturnValue, std::io::Error> {
+    pub fn handle_input(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<DialogReturnValue, Error> {
+        let mode = self.mode.clone();
+
+        if self.error.take().is_some() {
+            self.draw_split()?;
+            self.set_focus()?;
+        }
+
+        if let Some(control) = self.get_focused_control().filter(|c| c.captures_input(code, modifiers)) {
+            let result = control.handle_input(code, modifiers, mode)?;
+            return self.process_return_value(result);
+        }
+
         match (code, modifiers) {
             (KeyCode::Enter, _) => {
-                return Ok(DialogReturnValue { should_quit: true, dialog_result: Some(self.submit_result.clone()) });
+                let result = DialogReturnValue { should_quit: true, dialog_result: Some(self.submit_result.clone()), ..Default::default() };
+                return self.process_return_value(result);
             }
             (KeyCode::Esc, _) => {
-                return Ok(DialogReturnValue { should_quit: true, dialog_result: Some(self.cancel_result.clone()) });
+                return Ok(DialogReturnValue { should_quit: true, dialog_result: Some(self.cancel_result.clone()), ..Default::default() });
             }
             (KeyCode::Tab, _) => {
                 self.focus_next()?;
+                return Ok(DialogReturnValue::default());
             }
             (KeyCode::BackTab, _) => {
                 self.focus_previous()?;
+                return Ok(DialogReturnValue::default());
+            },
+            (KeyCode::PageDown, KeyModifiers::CONTROL) if !self.pages.is_empty() => {
+                self.set_page((self.page + 1) % self.pages.len())?;
+                return Ok(DialogReturnValue::default());
+            },
+            (KeyCode::PageUp, KeyModifiers::CONTROL) if !self.pages.is_empty() => {
+                self.set_page((self.page + self.pages.len() - 1) % self.pages.len())?;
+                return Ok(DialogReturnValue::default());
             },
             (KeyCode::Insert, _) => {
                 self.toggle_input();
@@ -384,15 +786,109 @@ impl Dialog {
         let mode = self.mode.clone();
 
         if let Some(focusable) = self.get_focused_control().map(|c| c as &mut Control) { 
-            return focusable.handle_input(code, modifiers, mode);
+            let result = focusable.handle_input(code, modifiers, mode)?;
+            return self.process_return_value(result);
         }
 
 
         Ok(DialogReturnValue::default())
     }
 
+    fn process_return_value(&mut self, mut value: DialogReturnValue) -> Result<DialogReturnValue, Error> {
+        if value.submit {
+            value.should_quit = true;
+            value.dialog_result = Some(self.submit_result.clone());
+        }
+        if value.redraw {
+            self.redraw()?;
+        } else if self.controls.iter().any(|c| c.get_status().is_some()) {
+            self.draw_split()?;
+            self.set_focus()?;
+        }
+
+        if value.should_quit && !self.is_dismissal(value.dialog_result.as_ref()) {
+            if !self.validate()? {
+                return Ok(DialogReturnValue::default());
+            }
+            self.save_history()?;
+        }
+
+        Ok(value)
+    }
+
+    // Results that close the dialog without accepting its values skip validation and history.
+    fn is_dismissal(&self, result: Option<&DialogResult>) -> bool {
+        match result {
+            Some(result) => *result == self.cancel_result || matches!(result, DialogResult::Cancel | DialogResult::No | DialogResult::Abort | DialogResult::Back),
+            None => true
+        }
+    }
+
+    // Shows the first validation error and moves focus to the offending control.
+    fn validate(&mut self) -> Result<bool, std::io::Error> {
+        let failed = self.controls.iter().find_map(|c| c.validate().err().map(|e| (c.get_tab_index(), c.get_field_index(), e)));
+
+        let Some((tab_index, field_index, error)) = failed else {
+            return Ok(true);
+        };
+
+        self.defocus()?;
+        if let Some(tab_index) = tab_index {
+            self.focused = tab_index;
+        }
+        self.error = Some(error);
+
+        // Errors on another tab bring that tab to the front.
+        let page = field_index.and_then(|index| self.pages.iter().position(|page| page.indexes.contains(&index)));
+        match page {
+            Some(page) if page != self.page => {
+                self.page = page;
+                self.redraw()?;
+            },
+            _ => {
+                self.draw_split()?;
+                self.set_focus()?;
+            }
+        }
+        stdout().queue(Print('\u{7}'))?.flush()?;
+
+        Ok(false)
+    }
+
+    fn save_history(&mut self) -> Result<(), Error> {
+        if let Some(history) = &mut self.history {
+            self.controls.iter().for_each(|control| {
+                if let Control::TextField(field) = control {
+                    if let (Some(key), Some((_, value))) = (field.get_history_key(), field.get_value()) {
+                        history.add(key, &value);
+                    }
+                }
+            });
+            history.save()?;
+        }
+
+        Ok(())
+    }
+
+    // Repaints everything without resetting the focused control, e.g. after a popup has closed.
+    fn redraw(&mut self) -> Result<(), std::io::Error> {
+        self.draw_overlay()?;
+        self.draw_border()?;
+        self.draw_title()?;
+        self.draw_split()?;
+        self.draw_controls()?;
+        self.set_focus()?;
+
+        stdout().flush()?;
+        Ok(())
+    }
+
     fn hide_focus(&mut self) -> Result<(), std::io::Error> {
-        self.controls.iter_mut().try_for_each(|c| c.hide_focus_indicator())?;
+        let hidden = self.hidden_indexes();
+        self.controls
+            .iter_mut()
+            .filter(|c| !is_hidden(c, &hidden) && !is_hidden(c, &self.offscreen))
+            .try_for_each(|c| c.hide_focus_indicator())?;
 
         Ok(())
     }
@@ -413,8 +909,41 @@ impl Dialog {
         Ok(())
     }
 
-    fn calc_size(&mut self) ->Result<(), std::io::Error> {
-        self.size = Some((self.min_width, self.min_height).into());
+    fn calc_size(&mut self) -> Result<(), Error> {
+        // Dialogs taller than the terminal scroll their body instead.
+        let body_width = self.body_width + 2 * self.margin.x + 2;
+        let (width, height) = match &self.screen_size {
+            Some(screen) => (self.width.resolve(self.min_width, screen.width).max(body_width).min(screen.width), screen.height),
+            None => (self.width.resolve(self.min_width, 0).max(body_width), usize::MAX)
+        };
+
+        // Buttons that don't fit the width wrap onto extra rows below the first.
+        let rows = wrap_buttons(&self.controls, width.saturating_sub(2 + 2 * self.margin.x)).len().max(1);
+
+        // Anything smaller can't show the border, the buttons and a row of the body.
+        if let Some(screen) = &self.screen_size {
+            let strip = if self.pages.is_empty() { 0 } else { 2 };
+            let button = self.controls
+                .iter()
+                .filter(|c| matches!(c, Control::Button(_)))
+                .map(|c| c.get_width() + BUTTON_PADDING)
+                .max()
+                .unwrap_or(0);
+            let required = (
+                (button.max(1) + 2 * self.margin.x + 2).max(body_width),
+                3 + rows + 2 * self.margin.y + strip + self.body_height.min(1)
+            );
+
+            if screen.width < required.0 || screen.height < required.1 {
+                return Err(Error::TerminalTooSmall { width: required.0, height: required.1 });
+            }
+            self.size = Some((
+                width.max(required.0),
+                self.height.resolve(self.min_height + rows - 1, screen.height).clamp(required.1, height)
+            ).into());
+        } else {
+            self.size = Some((width, self.height.resolve(self.min_height + rows - 1, 0)).into());
+        }
 
         Ok(())
     }
@@ -425,9 +954,43 @@ impl Dialog {
         Ok(())
     }
 
+    // Remembers the line below the cursor, scrolling the terminal up to make room for the dialog.
+    fn calc_inline_origin(&mut self) -> Result<(), std::io::Error> {
+        if self.inline_origin.is_some() {
+            return Ok(());
+        }
+
+        let (column, row) = cursor::position()?;
+        let (column, mut row) = (column as usize, row as usize + 1);
+        if let (Some(size), Some(screen_size)) = (&self.size, &self.screen_size) {
+            let overflow = (row + size.height).saturating_sub(screen_size.height);
+            if overflow > 0 {
+                stdout().queue(ScrollUp(overflow as u16))?.flush()?;
+                row -= overflow.min(row);
+            }
+        }
+        self.inline_origin = Some((column, row).into());
+
+        Ok(())
+    }
+
     fn calc_pos(&mut self) {
         if let (Some(size), Some(screen_size)) = (&self.size, &self.screen_size) {
-            self.position = Some((screen_size.width / 2 - size.width / 2, screen_size.height / 2 - size.height / 2).into());
+            let screen = ((0, 0).into(), (screen_size.width, screen_size.height));
+            let (x, y) = match &self.placement {
+                Placement::Center => anchor(Anchor::Center, &screen, size, &Position::default()),
+                Placement::Absolute(position) => (position.x, position.y),
+                Placement::Anchored(to, offset) => anchor(*to, &screen, size, offset),
+                Placement::Relative { parent, size: parent_size, anchor: to, offset } => {
+                    anchor(*to, &(parent.clone(), *parent_size), size, offset)
+                },
+                Placement::Inline => self.inline_origin.as_ref().map(|origin| (origin.x, origin.y)).unwrap_or_default()
+            };
+
+            self.position = Some((
+                x.min(screen_size.width.saturating_sub(size.width)),
+                y.min(screen_size.height.saturating_sub(size.height))
+            ).into());
             self.set_control_positions();
         }
     }
@@ -435,30 +998,165 @@ impl Dialog {
     fn set_control_positions(&mut self) {
         let specs = self.get_dialog_specs();
         if let Some(specs) = specs {
-            self.controls.iter_mut().for_each(|c| {
-                match c {
-                    Control::TextField(field) => {
-                        let x = specs.position.x + 1 + specs.margin.x + specs.max_name_len - field.get_name().len();
-                        let y = specs.position.y + 1 + specs.margin.y + 2*field.get_field_index();
-                        field.set_position((x, y).into());
-                    },
-                    Control::Button(button) => {
-                        if let Some(button_count) = &self.button_count {
-                            let y = specs.position.y + specs.size.height - 2;
-                            let x = match (&button.index, button_count) {
-                                (_, ButtonCount::One) | (ButtonCount::Two, ButtonCount::Three) => specs.position.x + specs.size.width/2 - button.name.len(),
-                                (ButtonCount::One, ButtonCount::Two) | (ButtonCount::One, ButtonCount::Three) => specs.position.x + specs.margin.x+1,
-                                (ButtonCount::Two, ButtonCount::Two) | (ButtonCount::Three, ButtonCount::Three) => specs.position.x+specs.size.width-specs.margin.x-button.name.len() - 1,    
-                                _ => panic!("Invalid button specs")
-
-                            };
-                            button.set_position((x, y).into());
-                        }
-                    },
+            // Every page shares the area below the tab strip.
+            let left = specs.position.x + 1 + specs.margin.x;
+            let top = specs.position.y + 1 + specs.margin.y + if self.pages.is_empty() { 0 } else { 2 };
+            let viewport = self.viewport_height();
+
+            // Lists and text boxes taller than the body give up rows so they can be shown whole.
+            self.controls.iter_mut().for_each(|c| c.fit_height(viewport));
+            self.body_height = match self.pages.is_empty() {
+                true => self.layout.measure(&self.controls).height,
+                false => self.pages.iter().map(|page| page.layout.measure(&self.controls).height).max().unwrap_or(0)
+            };
+
+            let content = (
+                specs.size.width.saturating_sub(2 + 2 * specs.margin.x),
+                self.body_height.max(viewport)
+            );
+            if self.pages.is_empty() {
+                self.layout.arrange(&mut self.controls, (left, top).into(), content.into());
+            } else {
+                for page in &self.pages {
+                    page.layout.arrange(&mut self.controls, (left, top).into(), content.into());
                 }
-            });
+            }
+
+            // Moves the controls up by the scroll offset, hiding those that aren't entirely in view.
+            // Labels and group frames can be cut off at the edges of the body instead.
+            self.scroll = self.scroll.min(content.1 - viewport);
+            self.rows.clear();
+            self.offscreen.clear();
+            for control in self.controls.iter_mut() {
+                if let Some(index) = control.get_field_index() {
+                    let position = control.get_position();
+                    let (row, height) = (position.y.saturating_sub(top), control.get_height());
+                    self.rows.push((index, row, height));
+
+                    match visible_rows(row, height, self.scroll, viewport) {
+                        Some((0, count)) if count == height => {
+                            control.set_clip(None);
+                            control.set_position((position.x, position.y - self.scroll).into());
+                        },
+                        Some((skip, count)) if control.set_clip(Some((skip, count))) => {
+                            control.set_position((position.x, top + row + skip - self.scroll).into());
+                        },
+                        _ => self.offscreen.push(index)
+                    }
+                }
+            }
+
+            let inner = specs.size.width.saturating_sub(2 + 2 * specs.margin.x);
+            let left = specs.position.x + 1 + specs.margin.x;
+            let rows = self.button_rows();
+            let bottom = (specs.position.y + specs.size.height).saturating_sub(1 + rows.len());
+            for (row, buttons) in rows.iter().enumerate() {
+                let widths = buttons.iter().map(|i| self.controls[*i].get_width() + BUTTON_PADDING).collect::<Vec<_>>();
+                for (i, x) in buttons.iter().zip(align_buttons(self.button_alignment, &widths, inner)) {
+                    self.controls[*i].set_position((left + x + BUTTON_PADDING / 2, bottom + row).into());
+                }
+            }
+        }
+    }
+}
+
+// Scroll offset that shows the `height` rows starting at `row` of a body `body_height` rows tall,
+// moving as little as possible from `scroll`. Scrolls all the way when that still shows the rows,
+// so labels above the first field and below the last one come into view as well. Rows taller
+// than the viewport are shown from their top.
+fn scroll_to(row: usize, height: usize, scroll: usize, viewport: usize, body_height: usize) -> usize {
+    let max_scroll = body_height.saturating_sub(viewport);
+
+    if row < scroll {
+        if row + height <= viewport { 0 } else { row }
+    } else if row + height > scroll + viewport {
+        if row >= max_scroll { max_scroll } else { (row + height - viewport).min(row) }
+    } else {
+        scroll
+    }
+}
+
+// The part of the `height` rows starting at `row` that a viewport scrolled to `scroll` shows,
+// as rows skipped from the top and rows shown. `None` when none of them are in view.
+fn visible_rows(row: usize, height: usize, scroll: usize, viewport: usize) -> Option<(usize, usize)> {
+    let (first, last) = (row.max(scroll), (row + height).min(scroll + viewport));
+    (first < last).then(|| (first - row, last - first))
+}
+
+// Top left corner that puts a dialog of `size` against the anchor point of `area`, moved
+// inwards by `offset`. Offsets from the middle move right and down.
+fn anchor(anchor: Anchor, area: &(Position, (usize, usize)), size: &Size, offset: &Position) -> (usize, usize) {
+    let (origin, (width, height)) = area;
+    let free = (width.saturating_sub(size.width), height.saturating_sub(size.height));
+
+    let x = match anchor {
+        Anchor::TopLeft | Anchor::Left | Anchor::BottomLeft => offset.x,
+        Anchor::Top | Anchor::Center | Anchor::Bottom => free.0 / 2 + offset.x,
+        Anchor::TopRight | Anchor::Right | Anchor::BottomRight => free.0.saturating_sub(offset.x)
+    };
+    let y = match anchor {
+        Anchor::TopLeft | Anchor::Top | Anchor::TopRight => offset.y,
+        Anchor::Left | Anchor::Center | Anchor::Right => free.1 / 2 + offset.y,
+        Anchor::BottomLeft | Anchor::Bottom | Anchor::BottomRight => free.1.saturating_sub(offset.y)
+    };
+
+    (origin.x + x, origin.y + y)
+}
+
+// Left edge of each button in a row of buttons `widths` wide, padding included, within `inner` columns.
+fn align_buttons(alignment: ButtonAlignment, widths: &[usize], inner: usize) -> Vec<usize> {
+    let used = widths.iter().sum::<usize>();
+    let free = inner.saturating_sub(used + BUTTON_SPACING * widths.len().saturating_sub(1));
+
+    // Spread shares out everything left over between the buttons instead of keeping them together.
+    let (start, gaps) = match (alignment, widths.len()) {
+        (ButtonAlignment::Left, _) => (0, 0),
+        (ButtonAlignment::Right, _) => (free, 0),
+        (ButtonAlignment::Center, _) | (ButtonAlignment::Spread, 1) => (free / 2, 0),
+        (ButtonAlignment::Spread, _) => (0, free)
+    };
+
+    let mut x = start;
+    widths.iter().enumerate().map(|(n, width)| {
+        if n > 0 {
+            x += gaps * n / (widths.len() - 1) - gaps * (n - 1) / (widths.len() - 1);
+        }
+        let left = x;
+        x += width + BUTTON_SPACING;
+        left
+    }).collect()
+}
+
+// Splits the buttons into rows no wider than `width`, in the order they were added.
+fn wrap_buttons(controls: &[Control], width: usize) -> Vec<Vec<usize>> {
+    let mut buttons = controls
+        .iter()
+        .enumerate()
+        .filter_map(|(i, c)| match c {
+            Control::Button(button) => Some((button.order, i)),
+            _ => None
+        })
+        .collect::<Vec<_>>();
+    buttons.sort();
+
+    let mut rows: Vec<Vec<usize>> = Vec::new();
+    let mut used = 0;
+    for (_, i) in buttons {
+        let control = &controls[i];
+        let length = control.get_width() + BUTTON_PADDING;
+        match rows.last_mut() {
+            Some(row) if used + BUTTON_SPACING + length <= width => {
+                row.push(i);
+                used += BUTTON_SPACING + length;
+            },
+            _ => {
+                rows.push(vec![i]);
+                used = length;
+            }
         }
     }
+
+    rows
 }
 
 #[derive(Debug)]
@@ -470,11 +1168,17 @@ pub struct DialogBuilder {
     colors: DialogColors,
     overlay: bool,
     fill: bool,
-    min_width: usize,
-    min_height: usize,
+    layout: Option<Layout>,
+    pages: Vec<(String, Layout)>,
     submit_result: DialogResult,
     cancel_result: DialogResult,
-    button_count: Option<ButtonCount>
+    button_alignment: ButtonAlignment,
+    width: Dimension,
+    height: Dimension,
+    placement: Placement,
+    history: Option<History>,
+    data: Option<FormData>,
+    size: (usize, usize)
 }
 
 impl Default for DialogBuilder {
@@ -487,11 +1191,17 @@ impl Default for DialogBuilder {
             colors: DialogColors::default(),
             overlay: false,
             fill: true,
-            min_width: 2,
-            min_height: 4,
+            layout: None,
+            pages: Vec::new(),
             submit_result: DialogResult::Ok,
             cancel_result: DialogResult::Cancel,
-            button_count: None
+            button_alignment: ButtonAlignment::default(),
+            width: Dimension::Auto,
+            height: Dimension::Auto,
+            placement: Placement::Center,
+            history: None,
+            data: None,
+            size: (0, 0)
          }
     }
 }
@@ -510,37 +1220,33 @@ impl DialogBuilder {
         self
     }
 
-    pub fn add_control(mut self, control: Control) -> Self {
-
-        let new_dimensions = match &control {
-            Control::TextField(field) => {
-                (self.min_width.max(4 + field.get_display_window() + field.get_name().len() + 2 * self.margin.x), self.min_height + 2)
-            },
-            Control::Button(button) => {
-                self.button_count = match self.button_count {
-                    None => Some(ButtonCount::One),
-                    Some(ButtonCount::One) => Some(ButtonCount::Two),
-                    Some(ButtonCount::Two) => Some(ButtonCount::Three),
-                    Some(ButtonCount::Three) => panic!("You can only add 3 buttons.")
-                };
-                (self.min_width.max(button.name.len() + 2), self.min_height)
-            },
-        };
+    pub fn add_control(mut self, mut control: Control) -> Self {
+        if let Control::Button(button) = &mut control {
+            button.order = self.controls.iter().filter(|c| matches!(c, Control::Button(_))).count();
+        }
 
-        self.min_width = new_dimensions.0;
-        self.min_height = new_dimensions.1;
+        self.controls.push(control);
 
+        self
+    }
 
-        self.controls.push(control);
+    pub fn set_margin(mut self, margin: Position) -> Self {
+        self.margin = margin;
 
         self
     }
 
-    pub fn set_margin(mut self, margin: Position) -> Self {        
-        self.min_width += margin.x * 2;
-        self.min_height += margin.y * 2;
+    /// Arranges the body controls, which otherwise stack vertically in field index order.
+    pub fn set_layout(mut self, layout: Layout) -> Self {
+        self.layout = Some(layout);
 
-        self.margin = margin;
+        self
+    }
+
+    /// Adds a tab holding the controls placed by `layout`. Once a dialog has pages, only the
+    /// controls on them are shown, one page at a time, and `set_layout` is ignored.
+    pub fn add_page(mut self, title: impl Into<String>, layout: Layout) -> Self {
+        self.pages.push((title.into(), layout));
 
         self
     }
@@ -575,34 +1281,190 @@ impl DialogBuilder {
         self
     }
 
-    pub fn build(self) -> Dialog {
+    pub fn set_button_alignment(mut self, alignment: ButtonAlignment) -> Self {
+        self.button_alignment = alignment;
+
+        self
+    }
+
+    pub fn set_history(mut self, history: History) -> Self {
+        self.history = Some(history);
+
+        self
+    }
+
+    /// Makes the dialog at least `width` x `height`, including the border.
+    pub fn set_min_size(mut self, width: usize, height: usize) -> Self {
+        self.size = (width, height);
+
+        self
+    }
+
+    /// Width including the border, instead of fitting the content. Never narrower than the body, which can't scroll sideways.
+    pub fn set_width(mut self, width: Dimension) -> Self {
+        self.width = width;
+
+        self
+    }
+
+    /// Height including the border, instead of fitting the content. A body that doesn't fit scrolls.
+    pub fn set_height(mut self, height: Dimension) -> Self {
+        self.height = height;
+
+        self
+    }
+
+    pub fn set_placement(mut self, placement: Placement) -> Self {
+        self.placement = placement;
+
+        self
+    }
+
+    // First tab index not taken by the controls added so far.
+    pub(crate) fn next_tab_index(&self) -> usize {
+        self.controls.iter().filter_map(|c| c.get_tab_index()).max().map(|index| index + 1).unwrap_or(0)
+    }
+
+    /// Initial values, matched to controls by name (case and punctuation insensitive).
+    pub fn set_data(mut self, data: FormData) -> Self {
+        self.data = Some(data);
+
+        self
+    }
+
+    /// Checks the configuration and builds the dialog, failing if two controls share a name or
+    /// tab index, or the layout refers to controls that weren't added.
+    pub fn build(self) -> Result<Dialog, Error> {
+        let border_chars = BorderChars::new(self.borders);
+        let layout = self.layout.unwrap_or_else(|| Layout::from_controls(&self.controls));
         let mut controls = self.controls.clone();
         controls.sort_by_key(|c| c.get_tab_index());
         controls.iter_mut().for_each(|c| match c {
             Control::TextField(f) => f.set_colors(self.colors.fields.clone()),
             Control::Button(b) => b.set_colors(self.colors.buttons.clone()),
+            Control::Checkbox(c) => c.set_colors(self.colors.fields.clone()),
+            Control::Label(l) => l.set_colors(self.colors.fields.label),
+            Control::List(l) => l.set_colors(self.colors.lists.clone()),
+            Control::Gauge(g) => g.set_colors(self.colors.gauges.clone()),
+            Control::TextBox(t) => t.set_colors(self.colors.text_boxes.clone()),
+            Control::Tree(t) => t.set_colors(self.colors.lists.clone(), border_chars.guides.clone()),
+            Control::Table(t) => t.set_colors(self.colors.tables.clone()),
+            Control::BuildList(b) => b.set_colors(self.colors.lists.clone()),
+            Control::FileBrowser(f) => f.set_colors(self.colors.fields.clone(), self.colors.lists.clone()),
+            Control::Group(g) => g.set_colors(self.colors.border),
         });
-        
-        Dialog {
+        if let Some(data) = &self.data {
+            controls.iter_mut().for_each(|c| if let Some(value) = data.get_by_key(&c.get_name()) {
+                c.set_value(value);
+            });
+        }
+        if let Some(history) = &self.history {
+            controls.iter_mut().for_each(|c| if let Control::TextField(f) = c {
+                if let Some(key) = f.get_history_key().map(String::from) {
+                    f.load_history(history.get(&key));
+                }
+            });
+        }
+
+        let pages = self.pages.into_iter().map(|(title, layout)| Ok(Page {
+            indexes: layout.nested_indexes(&controls)?,
+            title,
+            layout
+        })).collect::<Result<Vec<_>, Error>>()?;
+        validate(&controls, &layout, &pages)?;
+
+        // Room for the body, the button bar below the split and the title, inside the border and margin.
+        let (content, body_height) = measure_body(&layout, &pages, &controls);
+        // Wide enough to keep the buttons on one row, unless the terminal is narrower.
+        let buttons = controls.iter().filter(|c| matches!(c, Control::Button(_))).map(|c| c.get_width() + BUTTON_PADDING).collect::<Vec<_>>();
+        let button_bar = buttons.iter().sum::<usize>() + BUTTON_SPACING * buttons.len().saturating_sub(1);
+        let min_width = (content.width + 2 * self.margin.x)
+            .max(button_bar + 2 * self.margin.x)
+            .max(self.title.chars().count() + 4)
+            + 2;
+        let min_height = content.height + 2 * self.margin.y + 4;
+
+        Ok(Dialog {
             title: self.title,
-            border_chars: BorderChars::new(self.borders),
+            border_chars,
             controls,
             margin: self.margin,
+            layout,
+            pages,
+            body_width: content.width,
+            body_height,
             colors: self.colors,
             overlay: self.overlay,
             fill: self.fill,
             submit_result: self.submit_result,
             cancel_result: self.cancel_result,
-            min_height: self.min_height,
-            min_width: self.min_width,
-            button_count: self.button_count,
+            min_height: min_height.max(self.size.1),
+            min_width: min_width.max(self.size.0),
+            button_alignment: self.button_alignment,
+            width: self.width,
+            height: self.height,
+            placement: self.placement,
+            history: self.history,
             ..Default::default()            
-        }
+        })
         
     }
 }
 
-#[derive(Default, Debug, Clone)]
+// Size of the body including any tab strip, and the height of the tallest page alone.
+fn measure_body(layout: &Layout, pages: &[Page], controls: &[Control]) -> (Size, usize) {
+    if pages.is_empty() {
+        let content = layout.measure(controls);
+        let height = content.height;
+        return (content, height);
+    }
+
+    // The tab strip and a blank row above the tallest page.
+    let strip = pages.iter().map(|page| page.title.chars().count() + 3).sum::<usize>().saturating_sub(1);
+    let sizes = pages.iter().map(|page| page.layout.measure(controls)).collect::<Vec<_>>();
+    let height = sizes.iter().map(|size| size.height).max().unwrap_or(0);
+    ((sizes.iter().map(|size| size.width).max().unwrap_or(0).max(strip), height + 2).into(), height)
+}
+
+// Names and tab indexes must be unique, and layouts must place every body control exactly once.
+fn validate(controls: &[Control], layout: &Layout, pages: &[Page]) -> Result<(), Error> {
+    let mut names = HashSet::new();
+    if let Some((name, _)) = controls.iter().filter_map(|c| c.get_value()).find(|(name, _)| !names.insert(name.clone())) {
+        return Err(Error::DuplicateName(name));
+    }
+
+    let mut tab_indexes = HashSet::new();
+    if let Some(index) = controls.iter().filter_map(|c| c.get_tab_index()).find(|index| !tab_indexes.insert(*index)) {
+        return Err(Error::DuplicateTabIndex(index));
+    }
+
+    let placed = match pages.is_empty() {
+        true => layout.nested_indexes(controls)?,
+        false => pages.iter().flat_map(|page| page.indexes.iter().copied()).collect()
+    };
+    let mut seen = HashSet::new();
+    for index in placed {
+        if !controls.iter().any(|c| c.get_field_index() == Some(index)) {
+            return Err(Error::InvalidLayout(format!("no control with field index {}", index)));
+        }
+        if !seen.insert(index) {
+            return Err(Error::InvalidLayout(format!("control {} is placed more than once", index)));
+        }
+    }
+
+    // A control left out would be drawn at the corner of the terminal and still take focus.
+    if let Some(index) = controls.iter().filter_map(|c| c.get_field_index()).find(|index| !seen.contains(index)) {
+        return Err(Error::InvalidLayout(format!("control {} isn't placed by the layout", index)));
+    }
+
+    Ok(())
+}
+
+fn is_hidden(control: &Control, hidden: &[usize]) -> bool {
+    control.get_field_index().is_some_and(|index| hidden.contains(&index))
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
 pub enum DialogResult {
     #[default]
     Ok,
@@ -612,21 +1474,125 @@ pub enum DialogResult {
     Ignore,
     Yes,
     No,
+    Back,
+    Next
+}
+
+impl Display for DialogResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DialogResult::Ok => "ok",
+            DialogResult::Cancel => "cancel",
+            DialogResult::Abort => "abort",
+            DialogResult::Retry => "retry",
+            DialogResult::Ignore => "ignore",
+            DialogResult::Yes => "yes",
+            DialogResult::No => "no",
+            DialogResult::Back => "back",
+            DialogResult::Next => "next"
+        })
+    }
+}
+
+impl FromStr for DialogResult {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ok" => Ok(DialogResult::Ok),
+            "cancel" => Ok(DialogResult::Cancel),
+            "abort" => Ok(DialogResult::Abort),
+            "retry" => Ok(DialogResult::Retry),
+            "ignore" => Ok(DialogResult::Ignore),
+            "yes" => Ok(DialogResult::Yes),
+            "no" => Ok(DialogResult::No),
+            "back" => Ok(DialogResult::Back),
+            "next" => Ok(DialogResult::Next),
+            _ => Err(format!("unknown dialog result {:?}", s))
+        }
+    }
 }
 
 #[derive(Debug, Default)]
 pub struct DialogReturnValue {
     pub should_quit: bool,
     pub dialog_result: Option<DialogResult>,
+    pub(crate) redraw: bool,
+    // Set by controls to submit the dialog, as Enter would.
+    pub(crate) submit: bool
 }
 
-#[derive(Debug, Default)]
-pub struct FormData(HashMap<String, String>);
+#[derive(Debug, Default, Clone)]
+pub struct FormData(Vec<(String, String)>);
 
 impl FormData {
-    fn new(data: HashMap<String,String>) -> Self {
+    pub(crate) fn new(data: Vec<(String, String)>) -> Self {
         Self(data)
     }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Values of multi-selection controls such as check lists.
+    pub fn get_list(&self, name: &str) -> Vec<&str> {
+        self.get(name)
+            .map(|value| value.split(VALUE_SEPARATOR).filter(|v| !v.is_empty()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn get_parsed<T: FromStr>(&self, name: &str) -> Option<Result<T, T::Err>> {
+        self.get(name).map(|value| value.trim().parse())
+    }
+
+    /// Values in the order of the dialog's controls.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let (name, value) = (name.into(), value.into());
+
+        match self.0.iter_mut().find(|(key, _)| *key == name) {
+            Some((_, existing)) => *existing = value,
+            None => self.0.push((name, value))
+        }
+    }
+
+    // Looks a value up by its normalised key, so `first_name` finds "First Name".
+    pub(crate) fn get_by_key(&self, name: &str) -> Option<&str> {
+        let key = field_key(name);
+        self.0
+            .iter()
+            .find(|(k, _)| field_key(k) == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl IntoIterator for FormData {
+    type Item = (String, String);
+    type IntoIter = std::vec::IntoIter<(String, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl FromIterator<(String, String)> for FormData {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
 }
 
 
@@ -635,4 +1601,239 @@ pub(crate) enum TextMode {
     Overtype,
     #[default]
     Insert
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use crate::controls::{button::Button, field::Field, group::GroupBox};
+
+    use super::*;
+
+    fn size(width: usize, height: usize) -> Size {
+        (width, height).into()
+    }
+
+    fn field(name: &str, index: usize) -> Control {
+        Control::TextField(Field::new(name, 10, 10, Some(index), index))
+    }
+
+    #[test]
+    fn validate_accepts_a_layout_that_places_every_control_once() {
+        let controls = vec![field("Name", 0), field("Email", 1)];
+        let layout = Layout::horizontal([Layout::control(1), Layout::control(0)]);
+
+        assert!(validate(&controls, &layout, &[]).is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_duplicate_names() {
+        let controls = vec![field("Name", 0), field("Name", 1)];
+
+        assert!(matches!(validate(&controls, &Layout::from_controls(&controls), &[]), Err(Error::DuplicateName(name)) if name == "Name"));
+    }
+
+    #[test]
+    fn validate_rejects_duplicate_tab_indexes() {
+        let controls = vec![field("Name", 0), Control::TextField(Field::new("Email", 10, 10, Some(0), 1))];
+
+        assert!(matches!(validate(&controls, &Layout::from_controls(&controls), &[]), Err(Error::DuplicateTabIndex(0))));
+    }
+
+    #[test]
+    fn validate_rejects_layouts_that_misplace_controls() {
+        let controls = vec![field("Name", 0), field("Email", 1)];
+
+        let missing = Layout::vertical([Layout::control(0), Layout::control(1), Layout::control(2)]);
+        assert!(matches!(validate(&controls, &missing, &[]), Err(Error::InvalidLayout(_))));
+
+        let twice = Layout::vertical([Layout::control(0), Layout::control(1), Layout::control(0)]);
+        assert!(matches!(validate(&controls, &twice, &[]), Err(Error::InvalidLayout(_))));
+
+        let unplaced = Layout::vertical([Layout::control(0)]);
+        assert!(matches!(validate(&controls, &unplaced, &[]), Err(Error::InvalidLayout(_))));
+    }
+
+    #[test]
+    fn validate_checks_pages_instead_of_the_layout() {
+        let controls = vec![field("Name", 0), field("Email", 1)];
+        let page = |index| Page { title: String::new(), layout: Layout::control(index), indexes: vec![index] };
+
+        assert!(validate(&controls, &Layout::space(), &[page(0), page(1)]).is_ok());
+        assert!(matches!(validate(&controls, &Layout::space(), &[page(0)]), Err(Error::InvalidLayout(_))));
+    }
+
+    #[test]
+    fn dimensions_resolve_against_the_content_or_the_terminal() {
+        assert_eq!(Dimension::Auto.resolve(30, 80), 30);
+        assert_eq!(Dimension::Fixed(50).resolve(30, 80), 50);
+        assert_eq!(Dimension::Percent(50).resolve(30, 80), 40);
+        assert_eq!(Dimension::Percent(150).resolve(30, 80), 80);
+    }
+
+    #[test]
+    fn fixed_and_percentage_widths_fit_the_body() {
+        let width = |width| {
+            let mut dialog = DialogBuilder::new("Sized")
+                .add_control(Control::TextField(Field::new("Name", 30, 30, Some(0), 0)))
+                .set_width(width)
+                .build()
+                .unwrap();
+            dialog.screen_size = Some(size(80, 24));
+            dialog.calc_size().unwrap();
+            dialog.size.map(|size| size.width)
+        };
+
+        // "Name: " and the input take 36 columns inside the border.
+        assert_eq!(width(Dimension::Auto), Some(38));
+        assert_eq!(width(Dimension::Fixed(12)), Some(38));
+        assert_eq!(width(Dimension::Fixed(50)), Some(50));
+        assert_eq!(width(Dimension::Percent(10)), Some(38));
+        assert_eq!(width(Dimension::Percent(100)), Some(80));
+    }
+
+    #[test]
+    fn anchors_place_against_each_point_of_the_area() {
+        // A 10x4 dialog in a 40x20 area at (10, 5) has 30 columns and 16 rows to spare.
+        let area = ((10, 5).into(), (40, 20));
+        let dialog = size(10, 4);
+        let cases = [
+            (Anchor::TopLeft, (10, 5), (12, 6)),
+            (Anchor::Top, (25, 5), (27, 6)),
+            (Anchor::TopRight, (40, 5), (38, 6)),
+            (Anchor::Left, (10, 13), (12, 14)),
+            (Anchor::Center, (25, 13), (27, 14)),
+            (Anchor::Right, (40, 13), (38, 14)),
+            (Anchor::BottomLeft, (10, 21), (12, 20)),
+            (Anchor::Bottom, (25, 21), (27, 20)),
+            (Anchor::BottomRight, (40, 21), (38, 20))
+        ];
+
+        for (to, plain, moved) in cases {
+            assert_eq!(anchor(to, &area, &dialog, &Position::default()), plain, "{:?}", to);
+            assert_eq!(anchor(to, &area, &dialog, &(2, 1).into()), moved, "{:?} with an offset", to);
+        }
+    }
+
+    #[test]
+    fn anchor_offsets_stop_at_the_area() {
+        let area = ((10, 5).into(), (40, 20));
+
+        assert_eq!(anchor(Anchor::BottomRight, &area, &size(10, 4), &(50, 50).into()), (10, 5));
+        // A dialog larger than the area starts at its corner.
+        assert_eq!(anchor(Anchor::Center, &area, &size(60, 30), &Position::default()), (10, 5));
+    }
+
+    #[test]
+    fn placement_keeps_the_dialog_on_screen() {
+        let place = |placement| {
+            let mut dialog = DialogBuilder::new("Placed").set_placement(placement).build().unwrap();
+            dialog.screen_size = Some(size(80, 24));
+            dialog.size = Some(size(20, 5));
+            dialog.calc_pos();
+            dialog.position.map(|position| (position.x, position.y))
+        };
+
+        assert_eq!(place(Placement::Center), Some((30, 9)));
+        assert_eq!(place(Placement::Absolute((5, 3).into())), Some((5, 3)));
+        assert_eq!(place(Placement::Absolute((100, 100).into())), Some((60, 19)));
+        assert_eq!(place(Placement::Anchored(Anchor::Right, (90, 0).into())), Some((0, 9)));
+        assert_eq!(place(Placement::Relative { parent: (70, 20).into(), size: (30, 10), anchor: Anchor::Center, offset: Position::default() }), Some((60, 19)));
+    }
+
+    #[test]
+    fn scroll_to_moves_as_little_as_possible() {
+        // A 30 row body in a 10 row viewport.
+        let scroll = |row, height, scroll| scroll_to(row, height, scroll, 10, 30);
+
+        assert_eq!(scroll(3, 2, 0), 0);
+        assert_eq!(scroll(12, 2, 5), 5);
+        // Below the viewport, just far enough to show the last row.
+        assert_eq!(scroll(12, 2, 0), 4);
+        assert_eq!(scroll(25, 2, 0), 20);
+        // Above it, all the way to the top when that still shows the control.
+        assert_eq!(scroll(5, 2, 8), 0);
+        assert_eq!(scroll(15, 2, 18), 15);
+    }
+
+    #[test]
+    fn scroll_to_shows_the_top_of_tall_controls() {
+        let scroll = |row, height, scroll| scroll_to(row, height, scroll, 10, 40);
+
+        assert_eq!(scroll(12, 15, 0), 12);
+        assert_eq!(scroll(5, 15, 8), 5);
+        assert_eq!(scroll(0, 15, 0), 0);
+    }
+
+    #[test]
+    fn visible_rows_clip_at_the_edges_of_the_viewport() {
+        let visible = |row, height, scroll| visible_rows(row, height, scroll, 10);
+
+        assert_eq!(visible(5, 3, 0), Some((0, 3)));
+        assert_eq!(visible(8, 4, 0), Some((0, 2)));
+        assert_eq!(visible(2, 5, 4), Some((2, 3)));
+        assert_eq!(visible(0, 30, 5), Some((5, 10)));
+        assert_eq!(visible(12, 2, 0), None);
+        assert_eq!(visible(0, 2, 2), None);
+    }
+
+    #[test]
+    fn build_rejects_a_group_that_contains_itself() {
+        let result = DialogBuilder::new("Loop")
+            .add_control(field("Name", 0))
+            .add_control(Control::Group(GroupBox::new("Outer", Layout::vertical([Layout::control(0), Layout::control(1)]), 1)))
+            .build();
+
+        assert!(matches!(result, Err(Error::InvalidLayout(_))));
+    }
+
+    #[test]
+    fn build_rejects_groups_that_contain_each_other() {
+        let result = DialogBuilder::new("Loop")
+            .add_control(Control::Group(GroupBox::new("First", Layout::vertical([Layout::control(1)]), 0)))
+            .add_control(Control::Group(GroupBox::new("Second", Layout::vertical([Layout::control(0)]), 1)))
+            .set_layout(Layout::control(0))
+            .build();
+
+        assert!(matches!(result, Err(Error::InvalidLayout(_))));
+    }
+
+    fn button(label: &str, order: usize) -> Control {
+        let mut button = Button::new(label, None, DialogResult::Ok);
+        button.order = order;
+        Control::Button(button)
+    }
+
+    #[test]
+    fn wrap_buttons_keeps_the_order_they_were_added() {
+        // Padded widths: Cancel 10, Help 8, OK 6.
+        let controls = vec![button("OK", 2), field("Name", 1), button("Cancel", 0), button("Help", 1)];
+
+        assert_eq!(wrap_buttons(&controls, 40), [vec![2, 3, 0]]);
+        assert_eq!(wrap_buttons(&controls, 19), [vec![2, 3], vec![0]]);
+        assert_eq!(wrap_buttons(&controls, 18), [vec![2], vec![3, 0]]);
+        assert_eq!(wrap_buttons(&controls, 5), [vec![2], vec![3], vec![0]]);
+    }
+
+    #[test]
+    fn align_buttons_places_rows() {
+        let widths = [6, 10];
+
+        assert_eq!(align_buttons(ButtonAlignment::Left, &widths, 30), [0, 7]);
+        assert_eq!(align_buttons(ButtonAlignment::Right, &widths, 30), [13, 20]);
+        assert_eq!(align_buttons(ButtonAlignment::Center, &widths, 30), [6, 13]);
+        assert_eq!(align_buttons(ButtonAlignment::Spread, &widths, 30), [0, 20]);
+    }
+
+    #[test]
+    fn align_buttons_spreads_the_gaps_evenly() {
+        assert_eq!(align_buttons(ButtonAlignment::Spread, &[6, 6, 6], 30), [0, 12, 24]);
+        assert_eq!(align_buttons(ButtonAlignment::Spread, &[6, 6, 6], 31), [0, 12, 25]);
+        assert_eq!(align_buttons(ButtonAlignment::Spread, &[6], 30), [12]);
+    }
+
+    #[test]
+    fn align_buttons_starts_a_lone_wide_button_at_the_edge() {
+        for alignment in [ButtonAlignment::Left, ButtonAlignment::Center, ButtonAlignment::Right, ButtonAlignment::Spread] {
+            assert_eq!(align_buttons(alignment, &[40], 30), [0]);
+        }
+    }
+}
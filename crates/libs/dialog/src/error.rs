@@ -1,14 +1,46 @@
-// use derive_more::From;
-// use tracing::subscriber::SetGlobalDefaultError;
-// pub type Result<T> = core::result::Result<T, Error>;
+use std::fmt::Display;
 
-// #[derive(Debug, From)]
-// pub enum Error {
-//     Generic(String),
+use derive_more::From;
 
-//     #[from]
-//     IoError(std::io::Error),
+pub type Result<T> = core::result::Result<T, Error>;
 
-//     #[from]
-//     GlobalError(SetGlobalDefaultError)
-// }
\ No newline at end of file
+#[derive(Debug, From)]
+pub enum Error {
+    #[from]
+    Io(std::io::Error),
+
+    /// A dialog spec that couldn't be parsed or describes an impossible dialog.
+    InvalidSpec(String),
+
+    /// Two controls return their values under the same name.
+    DuplicateName(String),
+
+    /// Two controls share a tab index, so only one of them could ever take focus.
+    DuplicateTabIndex(usize),
+
+    /// A layout places a control that doesn't exist, places the same control twice, leaves a control out,
+    /// or nests a group inside itself.
+    InvalidLayout(String),
+
+    /// Form data that couldn't be converted to or from a struct, e.g. "abc" for a number field.
+    InvalidData(String),
+
+    /// The terminal can't fit the dialog's border, buttons and a row of its body.
+    TerminalTooSmall { width: usize, height: usize }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::InvalidSpec(e) => write!(f, "invalid dialog spec: {}", e),
+            Error::DuplicateName(name) => write!(f, "duplicate control name {:?}", name),
+            Error::DuplicateTabIndex(index) => write!(f, "duplicate tab index {}", index),
+            Error::InvalidLayout(e) => write!(f, "invalid layout: {}", e),
+            Error::InvalidData(e) => write!(f, "invalid form data: {}", e),
+            Error::TerminalTooSmall { width, height } => write!(f, "terminal too small, the dialog needs at least {}x{}", width, height)
+        }
+    }
+}
+
+impl std::error::Error for Error {}
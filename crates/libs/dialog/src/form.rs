@@ -0,0 +1,29 @@
+use std::fmt::Display;
+
+use crate::dialog::{DialogBuilder, FormData};
+
+/// A struct that can be edited through a dialog, usually implemented with `#[derive(Dialog)]`.
+pub trait DialogForm: Sized {
+    /// Number of controls added by `dialog_builder`, i.e. the first free tab index.
+    const FIELD_COUNT: usize;
+
+    fn dialog_builder() -> DialogBuilder;
+    fn from_form_data(data: &FormData) -> Result<Self, FormError>;
+}
+
+#[derive(Debug)]
+pub enum FormError {
+    Missing(String),
+    Invalid { field: String, message: String }
+}
+
+impl Display for FormError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormError::Missing(field) => write!(f, "missing value for {}", field),
+            FormError::Invalid { field, message } => write!(f, "invalid value for {}: {}", field, message)
+        }
+    }
+}
+
+impl std::error::Error for FormError {}
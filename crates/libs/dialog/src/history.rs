@@ -0,0 +1,163 @@
+use std::{collections::HashMap, fs, io::ErrorKind, path::{Path, PathBuf}};
+
+use crate::error::Error;
+
+#[derive(Debug, Clone)]
+pub struct History {
+    path: Option<PathBuf>,
+    max_size: usize,
+    entries: HashMap<String, Vec<String>>
+}
+
+impl History {
+    /// In-memory history, kept for the lifetime of the dialog only.
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            path: None,
+            max_size,
+            entries: HashMap::new()
+        }
+    }
+
+    /// History backed by a file, which doesn't have to exist yet.
+    pub fn load(path: impl AsRef<Path>, max_size: usize) -> Result<Self, Error> {
+        let mut history = Self {
+            path: Some(path.as_ref().to_path_buf()),
+            ..Self::new(max_size)
+        };
+
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into())
+        };
+
+        contents
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .for_each(|(key, value)| history.add(&unescape(key), &unescape(value)));
+
+        Ok(history)
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut keys = self.entries.keys().collect::<Vec<_>>();
+        keys.sort();
+
+        let contents = keys
+            .into_iter()
+            .flat_map(|key| self.entries[key].iter().map(move |value| format!("{}\t{}\n", escape(key), escape(value))))
+            .collect::<String>();
+
+        Ok(fs::write(path, contents)?)
+    }
+
+    /// Records a value as the most recent entry for `key`, dropping any older duplicate.
+    pub fn add(&mut self, key: &str, value: &str) {
+        if value.is_empty() {
+            return;
+        }
+
+        let values = self.entries.entry(key.to_string()).or_default();
+        values.retain(|v| v != value);
+        values.push(value.to_string());
+
+        if values.len() > self.max_size {
+            values.drain(..values.len() - self.max_size);
+        }
+    }
+
+    /// Values for `key`, oldest first.
+    pub fn get(&self, key: &str) -> &[String] {
+        self.entries.get(key).map(|v| v.as_slice()).unwrap_or_default()
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => result.push('\\')
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_moves_duplicates_to_the_end() {
+        let mut history = History::new(10);
+        history.add("name", "a");
+        history.add("name", "b");
+        history.add("name", "a");
+        history.add("name", "");
+
+        assert_eq!(history.get("name"), ["b", "a"]);
+        assert!(history.get("other").is_empty());
+    }
+
+    #[test]
+    fn add_drops_the_oldest_past_max_size() {
+        let mut history = History::new(2);
+        for value in ["a", "b", "c"] {
+            history.add("name", value);
+        }
+        history.add("other", "x");
+
+        assert_eq!(history.get("name"), ["b", "c"]);
+        assert_eq!(history.get("other"), ["x"]);
+    }
+
+    #[test]
+    fn escape_round_trips() {
+        for value in ["plain", "tab\there", "two\nlines", "back\\slash", "\\t literal", "trailing\\"] {
+            assert_eq!(unescape(&escape(value)), value);
+            assert!(!escape(value).contains(['\t', '\n']));
+        }
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let path = std::env::temp_dir().join(format!("dialog-history-test-{}", std::process::id()));
+        let mut history = History::load(&path, 5).unwrap();
+        history.add("key\twith tab", "first");
+        history.add("key\twith tab", "multi\nline\\value");
+        history.add("plain", "x");
+        history.save().unwrap();
+
+        let loaded = History::load(&path, 5).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.get("key\twith tab"), ["first", "multi\nline\\value"]);
+        assert_eq!(loaded.get("plain"), ["x"]);
+    }
+}
@@ -0,0 +1,465 @@
+use crate::{controls::{Control, UIElement}, error::Error, utils::{Position, Size}};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Direction {
+    Vertical,
+    Horizontal
+}
+
+#[derive(Debug, Clone)]
+enum LayoutKind {
+    // A body control, referred to by its field index.
+    Control(usize),
+    Space,
+    Stack(Direction, Vec<Layout>),
+    // Items filled in row by row, `usize` columns wide.
+    Grid(usize, Vec<Layout>)
+}
+
+/// Arranges the body controls of a dialog. The dialog is sized to fit the layout, so controls
+/// are placed by describing how they stack rather than by coordinates.
+///
+/// Controls with a label (fields and checkboxes) that sit directly in the same vertical stack,
+/// or the same grid column, have their labels right-aligned to a common column.
+#[derive(Debug, Clone)]
+pub struct Layout {
+    kind: LayoutKind,
+    spacing: usize,
+    column_spacing: usize,
+    stretch: bool,
+    min_size: (usize, usize),
+    max_size: Option<(usize, usize)>
+}
+
+impl Layout {
+    fn new(kind: LayoutKind, spacing: usize) -> Self {
+        Self {
+            kind,
+            spacing,
+            column_spacing: 2,
+            stretch: false,
+            min_size: (0, 0),
+            max_size: None
+        }
+    }
+
+    /// The control with the given field index.
+    pub fn control(index: usize) -> Self {
+        Self::new(LayoutKind::Control(index), 0)
+    }
+
+    /// Empty space, usually combined with `set_stretch` to push the following items to the far edge.
+    pub fn space() -> Self {
+        Self::new(LayoutKind::Space, 0)
+    }
+
+    /// Items stacked top to bottom with a blank row between them.
+    pub fn vertical(items: impl IntoIterator<Item = Layout>) -> Self {
+        Self::new(LayoutKind::Stack(Direction::Vertical, items.into_iter().collect()), 1)
+    }
+
+    /// Items placed left to right, two columns apart.
+    pub fn horizontal(items: impl IntoIterator<Item = Layout>) -> Self {
+        Self::new(LayoutKind::Stack(Direction::Horizontal, items.into_iter().collect()), 2)
+    }
+
+    /// Items filled into `columns` columns row by row, e.g. a two column form. Each column aligns
+    /// its own labels and each row is as tall as its tallest item.
+    pub fn grid(columns: usize, items: impl IntoIterator<Item = Layout>) -> Self {
+        Self::new(LayoutKind::Grid(columns.max(1), items.into_iter().collect()), 1)
+    }
+
+    /// Gap between the items of a stack, in rows for vertical stacks and columns for horizontal ones.
+    pub fn set_spacing(mut self, spacing: usize) -> Self {
+        self.spacing = spacing;
+
+        self
+    }
+
+    /// Gap between the columns of a grid.
+    pub fn set_column_spacing(mut self, spacing: usize) -> Self {
+        self.column_spacing = spacing;
+
+        self
+    }
+
+    /// Gives the item a share of any room its stack has left over.
+    pub fn set_stretch(mut self, stretch: bool) -> Self {
+        self.stretch = stretch;
+
+        self
+    }
+
+    pub fn set_min_size(mut self, width: usize, height: usize) -> Self {
+        self.min_size = (width, height);
+
+        self
+    }
+
+    /// Limits the room the item is given, e.g. how far it stretches. An item is never made smaller than its content.
+    pub fn set_max_size(mut self, width: usize, height: usize) -> Self {
+        self.max_size = Some((width, height));
+
+        self
+    }
+
+    // Every body control stacked vertically in field index order, leaving out those inside a group.
+    pub(crate) fn from_controls(controls: &[Control]) -> Self {
+        let grouped = controls
+            .iter()
+            .filter_map(|c| match c {
+                Control::Group(group) => Some(group.get_layout().indexes()),
+                _ => None
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+
+        let mut indexes = controls
+            .iter()
+            .filter_map(|c| c.get_field_index())
+            .filter(|index| !grouped.contains(index))
+            .collect::<Vec<_>>();
+        indexes.sort();
+        indexes.dedup();
+
+        Self::vertical(indexes.into_iter().map(Self::control))
+    }
+
+    // Field indexes of the controls placed by this layout.
+    pub(crate) fn indexes(&self) -> Vec<usize> {
+        match &self.kind {
+            LayoutKind::Control(index) => vec![*index],
+            LayoutKind::Space => Vec::new(),
+            LayoutKind::Stack(_, items) | LayoutKind::Grid(_, items) => items.iter().flat_map(|item| item.indexes()).collect()
+        }
+    }
+
+    // Field indexes of the controls placed by this layout, including those inside its groups.
+    // A group whose layout contains itself, directly or through another group, is an error.
+    pub(crate) fn nested_indexes(&self, controls: &[Control]) -> Result<Vec<usize>, Error> {
+        self.collect_nested(controls, &mut Vec::new())
+    }
+
+    // `groups` holds the groups currently being expanded.
+    fn collect_nested(&self, controls: &[Control], groups: &mut Vec<usize>) -> Result<Vec<usize>, Error> {
+        let mut indexes = Vec::new();
+        for index in self.indexes() {
+            indexes.push(index);
+            if let Some(Control::Group(group)) = find(controls, index) {
+                if groups.contains(&index) {
+                    return Err(Error::InvalidLayout(format!("group {} contains itself", index)));
+                }
+                groups.push(index);
+                indexes.extend(group.get_layout().collect_nested(controls, groups)?);
+                groups.pop();
+            }
+        }
+        Ok(indexes)
+    }
+
+    pub(crate) fn measure(&self, controls: &[Control]) -> Size {
+        let (width, height) = match &self.kind {
+            LayoutKind::Control(index) => match find(controls, *index) {
+                Some(Control::Group(group)) => {
+                    let size = group.measure(controls);
+                    (size.width, size.height)
+                },
+                Some(control) => (control.get_width(), control.get_height()),
+                None => (0, 0)
+            },
+            LayoutKind::Space => (0, 0),
+            LayoutKind::Stack(Direction::Vertical, items) => {
+                let label_width = label_width(items, controls);
+                let width = items.iter().map(|item| item.indent(controls, label_width) + item.measure(controls).width).max().unwrap_or(0);
+                let height = items.iter().map(|item| item.measure(controls).height).sum::<usize>() + self.gaps(items);
+                (width, height)
+            },
+            LayoutKind::Stack(Direction::Horizontal, items) => {
+                let width = items.iter().map(|item| item.measure(controls).width).sum::<usize>() + self.gaps(items);
+                let height = items.iter().map(|item| item.measure(controls).height).max().unwrap_or(0);
+                (width, height)
+            },
+            LayoutKind::Grid(columns, items) => {
+                let (widths, heights) = self.tracks(*columns, items, controls);
+                let width = widths.iter().map(|(_, width)| width).sum::<usize>() + self.column_spacing * widths.len().saturating_sub(1);
+                let height = heights.iter().sum::<usize>() + self.spacing * heights.len().saturating_sub(1);
+                (width, height)
+            }
+        };
+
+        (width.max(self.min_size.0), height.max(self.min_size.1)).into()
+    }
+
+    // Positions the controls within the area starting at `position`.
+    pub(crate) fn arrange(&self, controls: &mut [Control], position: Position, size: Size) {
+        let size = self.clamp(size, controls);
+
+        match &self.kind {
+            LayoutKind::Control(index) => {
+                // A group takes the whole area and lays its children out inside its frame.
+                let mut children = None;
+                controls
+                    .iter_mut()
+                    .filter(|c| c.get_field_index() == Some(*index))
+                    .for_each(|c| match c {
+                        Control::Group(group) => {
+                            let (position, size) = group.set_bounds(position.clone(), size.clone());
+                            children = Some((group.get_layout().clone(), position, size));
+                        },
+                        c => c.set_position(position.clone())
+                    });
+
+                if let Some((layout, position, size)) = children {
+                    layout.arrange(controls, position, size);
+                }
+            },
+            LayoutKind::Space => {},
+            LayoutKind::Stack(direction, items) => {
+                let vertical = *direction == Direction::Vertical;
+                let label_width = if vertical { label_width(items, controls) } else { 0 };
+                let lengths = items.iter().map(|item| {
+                    let size = item.measure(controls);
+                    if vertical { size.height } else { size.width }
+                }).collect::<Vec<_>>();
+
+                let available = if vertical { size.height } else { size.width };
+                let extra = available.saturating_sub(lengths.iter().sum::<usize>() + self.gaps(items));
+                let stretchers = items.iter().filter(|item| item.stretch).count();
+
+                let mut offset = 0;
+                let mut stretched = 0;
+                for (item, length) in items.iter().zip(lengths) {
+                    let mut length = length;
+                    if item.stretch {
+                        length += extra / stretchers + usize::from(stretched < extra % stretchers);
+                        stretched += 1;
+                    }
+
+                    // The next item follows on from where a capped item actually ends.
+                    if vertical {
+                        let indent = item.indent(controls, label_width);
+                        let position = (position.x + indent, position.y + offset).into();
+                        let area = item.clamp((size.width.saturating_sub(indent), length).into(), controls);
+                        offset += area.height + self.spacing;
+                        item.arrange(controls, position, area);
+                    } else {
+                        let position = (position.x + offset, position.y).into();
+                        let area = item.clamp((length, size.height).into(), controls);
+                        offset += area.width + self.spacing;
+                        item.arrange(controls, position, area);
+                    }
+                }
+            },
+            LayoutKind::Grid(columns, items) => {
+                let (widths, heights) = self.tracks(*columns, items, controls);
+
+                let mut y = position.y;
+                for (row, height) in items.chunks(*columns).zip(heights) {
+                    let mut x = position.x;
+                    for (item, (label_width, width)) in row.iter().zip(&widths) {
+                        let indent = item.indent(controls, *label_width);
+                        item.arrange(controls, (x + indent, y).into(), (width - indent, height).into());
+                        x += width + self.column_spacing;
+                    }
+                    y += height + self.spacing;
+                }
+            }
+        }
+    }
+
+    // Label width and total width of each grid column, and the height of each row.
+    fn tracks(&self, columns: usize, items: &[Layout], controls: &[Control]) -> (Vec<(usize, usize)>, Vec<usize>) {
+        let widths = (0..columns.min(items.len())).map(|column| {
+            let cells = items.iter().skip(column).step_by(columns).collect::<Vec<_>>();
+            let label_width = label_width(cells.iter().copied(), controls);
+            let width = cells.iter().map(|cell| cell.indent(controls, label_width) + cell.measure(controls).width).max().unwrap_or(0);
+            (label_width, width)
+        }).collect();
+
+        let heights = items
+            .chunks(columns)
+            .map(|row| row.iter().map(|item| item.measure(controls).height).max().unwrap_or(0))
+            .collect();
+
+        (widths, heights)
+    }
+
+    fn gaps(&self, items: &[Layout]) -> usize {
+        self.spacing * items.len().saturating_sub(1)
+    }
+
+    // The area limited to the maximum size, but never smaller than the content.
+    fn clamp(&self, size: Size, controls: &[Control]) -> Size {
+        let Some((max_width, max_height)) = self.max_size else {
+            return size;
+        };
+
+        let content = self.measure(controls);
+        (size.width.min(max_width.max(content.width)), size.height.min(max_height.max(content.height))).into()
+    }
+
+    // Columns to shift a labelled control right so its label ends at `label_width`.
+    fn indent(&self, controls: &[Control], label_width: usize) -> usize {
+        match &self.kind {
+            LayoutKind::Control(index) => find(controls, *index)
+                .and_then(|c| c.get_label_width())
+                .map(|width| label_width - width)
+                .unwrap_or(0),
+            _ => 0
+        }
+    }
+}
+
+// Widest label among the controls placed directly in a stack or grid column.
+fn label_width<'a>(items: impl IntoIterator<Item = &'a Layout>, controls: &[Control]) -> usize {
+    items
+        .into_iter()
+        .filter_map(|item| match &item.kind {
+            LayoutKind::Control(index) => find(controls, *index).and_then(|c| c.get_label_width()),
+            _ => None
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+fn find(controls: &[Control], index: usize) -> Option<&Control> {
+    controls.iter().find(|c| c.get_field_index() == Some(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controls::{field::Field, group::GroupBox, label::Label};
+
+    use super::*;
+
+    fn field(name: &str, display_len: usize, index: usize) -> Control {
+        Control::TextField(Field::new(name, display_len, display_len, Some(index), index))
+    }
+
+    fn label(text: &str, index: usize) -> Control {
+        Control::Label(Label::new(text, index))
+    }
+
+    fn position(controls: &[Control], index: usize) -> (usize, usize) {
+        let position = find(controls, index).unwrap().get_position();
+        (position.x, position.y)
+    }
+
+    fn arrange(layout: &Layout, controls: &mut [Control], width: usize, height: usize) {
+        layout.arrange(controls, Position::default(), (width, height).into());
+    }
+
+    #[test]
+    fn vertical_stack_aligns_labels() {
+        // "Name: " and "Address: " take 6 and 9 columns ahead of the 10 column inputs.
+        let mut controls = vec![field("Name", 10, 0), field("Address", 10, 1)];
+        let layout = Layout::vertical([Layout::control(0), Layout::control(1)]);
+
+        let size = layout.measure(&controls);
+        assert_eq!((size.width, size.height), (19, 3));
+
+        arrange(&layout, &mut controls, size.width, size.height);
+        assert_eq!(position(&controls, 0), (3, 0));
+        assert_eq!(position(&controls, 1), (0, 2));
+    }
+
+    #[test]
+    fn horizontal_stack_doesnt_align_labels() {
+        let mut controls = vec![field("Name", 10, 0), field("Address", 10, 1)];
+        let layout = Layout::horizontal([Layout::control(0), Layout::control(1)]);
+
+        let size = layout.measure(&controls);
+        assert_eq!((size.width, size.height), (16 + 2 + 19, 1));
+
+        arrange(&layout, &mut controls, size.width, size.height);
+        assert_eq!(position(&controls, 0), (0, 0));
+        assert_eq!(position(&controls, 1), (18, 0));
+    }
+
+    #[test]
+    fn stretch_takes_the_room_left_over() {
+        let mut controls = vec![label("ab", 0), label("cd", 1)];
+        let layout = Layout::horizontal([Layout::control(0), Layout::space().set_stretch(true), Layout::control(1)]);
+
+        assert_eq!(layout.measure(&controls).width, 8);
+
+        arrange(&layout, &mut controls, 13, 1);
+        assert_eq!(position(&controls, 0), (0, 0));
+        assert_eq!(position(&controls, 1), (11, 0));
+    }
+
+    #[test]
+    fn stretch_splits_the_remainder_from_the_front() {
+        let mut controls = vec![label("ab", 0)];
+        let layout = Layout::horizontal([
+            Layout::space().set_stretch(true),
+            Layout::control(0),
+            Layout::space().set_stretch(true)
+        ]).set_spacing(0);
+
+        arrange(&layout, &mut controls, 7, 1);
+        assert_eq!(position(&controls, 0), (3, 0));
+    }
+
+    #[test]
+    fn grid_sizes_columns_and_rows_separately() {
+        let mut controls = vec![
+            field("Name", 10, 0),
+            field("Address", 10, 1),
+            field("Zip", 4, 2),
+            label("one\ntwo", 3)
+        ];
+        let layout = Layout::grid(2, (0..4).map(Layout::control));
+
+        // The first column aligns "Name" and "Zip", the second is as wide as the address field.
+        let size = layout.measure(&controls);
+        assert_eq!((size.width, size.height), (16 + 2 + 19, 1 + 1 + 2));
+
+        arrange(&layout, &mut controls, size.width, size.height);
+        assert_eq!(position(&controls, 0), (0, 0));
+        assert_eq!(position(&controls, 1), (18, 0));
+        assert_eq!(position(&controls, 2), (1, 2));
+        assert_eq!(position(&controls, 3), (18, 2));
+    }
+
+    #[test]
+    fn min_size_pads_the_measured_size() {
+        let controls = vec![field("Name", 10, 0)];
+
+        let size = Layout::control(0).set_min_size(30, 3).measure(&controls);
+        assert_eq!((size.width, size.height), (30, 3));
+
+        let size = Layout::control(0).set_min_size(5, 1).measure(&controls);
+        assert_eq!((size.width, size.height), (16, 1));
+    }
+
+    #[test]
+    fn max_size_limits_stretching_but_not_the_content() {
+        let mut controls = vec![label("ab", 0), label("cd", 1), field("Name", 10, 2)];
+        let layout = Layout::horizontal([
+            Layout::control(0).set_stretch(true).set_max_size(5, 1),
+            Layout::control(1),
+            Layout::control(2).set_max_size(10, 1)
+        ]);
+
+        // The field measures its full 16 columns whatever its maximum.
+        assert_eq!(layout.measure(&controls).width, 2 + 2 + 2 + 2 + 16);
+
+        // "ab" stretches to 5 columns rather than taking all 16 spare ones, and "cd" follows it.
+        arrange(&layout, &mut controls, 40, 1);
+        assert_eq!(position(&controls, 1), (7, 0));
+        assert_eq!(position(&controls, 2), (11, 0));
+    }
+
+    #[test]
+    fn nested_indexes_include_group_children() {
+        let controls = vec![
+            field("Name", 10, 0),
+            Control::Group(GroupBox::new("Address", Layout::vertical([Layout::control(2)]), 1)),
+            field("Street", 10, 2)
+        ];
+        let layout = Layout::vertical([Layout::control(0), Layout::control(1)]);
+
+        assert_eq!(layout.nested_indexes(&controls).unwrap(), vec![0, 1, 2]);
+    }
+}
@@ -1,10 +1,26 @@
 pub mod borders;
 pub mod controls;
 pub mod colors;
+pub mod completion;
 pub mod dialog;
+pub mod error;
+pub mod form;
+pub mod history;
+pub mod layout;
+#[cfg(feature = "serde")]
+pub mod serialization;
+#[cfg(feature = "spec")]
+pub mod spec;
+pub mod validation;
+pub mod wizard;
 mod line_buffer;
 mod utils;
 
+pub use error::{Error, Result};
+
+#[cfg(feature = "derive")]
+pub use dialog_derive::Dialog;
+
 
 
 
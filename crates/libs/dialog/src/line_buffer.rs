@@ -13,7 +13,8 @@ pub struct LineBuffer {
     pub buffer: String,
     colors: LineBufferColors,
     pos: usize,
-    window_start: usize
+    window_start: usize,
+    mask: Option<char>
 }
 
 impl LineBuffer {
@@ -26,10 +27,15 @@ impl LineBuffer {
             buffer: String::new(),
             colors: LineBufferColors::default(),
             pos: 0,
-            window_start: 0
+            window_start: 0,
+            mask: None
         }
     }
 
+    pub fn set_mask(&mut self, mask: Option<char>) {
+        self.mask = mask;
+    }
+
     pub fn set_colors(&mut self, colors: LineBufferColors) {
         self.colors = colors;
     }
@@ -73,6 +79,16 @@ impl LineBuffer {
         self.position.clone()
     }
     
+    pub fn set_value(&mut self, value: &str) {
+        self.buffer = value.chars().take(self.max_buffer_len).collect();
+        self.window_start = 0;
+        self.set_pos(self.buffer.len());
+    }
+
+    pub fn is_cursor_at_end(&self) -> bool {
+        self.pos == self.buffer.len()
+    }
+
     pub fn set_pos(&mut self, pos: usize) {
         self.pos = pos.min(self.buffer.len());
         self.adjust_visible_window();
@@ -190,9 +206,14 @@ impl LineBuffer {
         stdout().queue(SetColors(self.colors.focus))?;
         if has_left_text { stdout().queue(Print("<"))?; } else { stdout().queue(Print(" "))?; }
         
+        let visible_buffer = match self.mask {
+            Some(mask) => mask.to_string().repeat(visible_buffer.chars().count()),
+            None => visible_buffer.to_string()
+        };
+
         stdout()
             .queue(SetColors(self.colors.input))?
-            .queue(Print(visible_buffer))?;
+            .queue(Print(&visible_buffer))?;
         
         
         let pad_length = self.window_size.saturating_sub(visible_buffer.chars().count());
@@ -208,8 +229,30 @@ impl LineBuffer {
     
         Ok(())
     }
-    
-    
+
+    pub fn draw_ghost(&self, ghost: &str) -> Result<(), std::io::Error> {
+        if !self.is_cursor_at_end() {
+            return Ok(());
+        }
+
+        let visible_len = self.buffer.len() - self.window_start;
+        let available = self.window_size.saturating_sub(visible_len);
+        let max_len = self.max_buffer_len.saturating_sub(self.buffer.len());
+        let ghost: String = ghost.chars().take(available.min(max_len)).collect();
+
+        if ghost.is_empty() {
+            return Ok(());
+        }
+
+        stdout()
+            .queue(MoveTo((self.position.x + visible_len) as u16, self.position.y as u16))?
+            .queue(SetColors(self.colors.ghost))?
+            .queue(Print(ghost))?
+            .queue(MoveTo((self.position.x + visible_len) as u16, self.position.y as u16))?
+            .flush()?;
+
+        Ok(())
+    }
 }
 enum CharAddResult {
     Accepted,
@@ -0,0 +1,258 @@
+use std::fmt::Display;
+
+use serde::{
+    de::{self, value::{MapDeserializer, SeqDeserializer}, DeserializeOwned, IntoDeserializer, Visitor},
+    forward_to_deserialize_any, Deserializer, Serialize
+};
+use serde_json::Value;
+
+use crate::{controls::list::VALUE_SEPARATOR, dialog::FormData, error::Error, utils::field_key};
+
+// Errors raised while (de)serializing, reported as `Error::InvalidData`.
+#[derive(Debug)]
+struct FormDataError(String);
+
+impl Display for FormDataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for FormDataError {}
+
+impl de::Error for FormDataError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl From<FormDataError> for Error {
+    fn from(e: FormDataError) -> Self {
+        Error::InvalidData(e.0)
+    }
+}
+
+impl FormData {
+    /// Deserializes the values into `T`. Field names are matched in snake case, so
+    /// "First Name" fills a `first_name` field. The tags of a checklist fill a `Vec`.
+    pub fn to_struct<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        Ok(T::deserialize(FormDataDeserializer(self))?)
+    }
+
+    /// Flattens a struct (or map) of scalar values into form data, e.g. to pass to `DialogBuilder::set_data`.
+    /// Sequences become lists of tags, as a checklist returns them.
+    pub fn from_struct<T: Serialize>(value: &T) -> Result<Self, Error> {
+        match serde_json::to_value(value).map_err(|e| Error::InvalidData(e.to_string()))? {
+            Value::Object(map) => Ok(map.into_iter().map(|(key, value)| (key, value_to_string(value))).collect()),
+            other => Err(Error::InvalidData(format!("expected a struct or map, found {}", other)))
+        }
+    }
+}
+
+fn value_to_string(value: Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s,
+        Value::Array(values) => values.into_iter().map(value_to_string).collect::<Vec<_>>().join(&VALUE_SEPARATOR.to_string()),
+        other => other.to_string()
+    }
+}
+
+struct FormDataDeserializer<'a>(&'a FormData);
+
+impl<'de, 'a> Deserializer<'de> for FormDataDeserializer<'a> {
+    type Error = FormDataError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let entries = self.0
+            .iter()
+            .map(|(key, value)| (field_key(key), ValueDeserializer(value.to_string())));
+
+        visitor.visit_map(MapDeserializer::new(entries))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct ValueDeserializer(String);
+
+impl<'de> IntoDeserializer<'de, FormDataError> for ValueDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                let value = self.0.trim().parse().map_err(|e| FormDataError(format!("invalid value {:?}: {}", self.0, e)))?;
+                visitor.$visit(value)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer {
+    type Error = FormDataError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_string(self.0)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0.trim().to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => visitor.visit_bool(true),
+            "false" | "no" | "off" | "0" | "" => visitor.visit_bool(false),
+            _ => Err(FormDataError(format!("invalid value {:?}: expected a boolean", self.0)))
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    // An empty input is treated as "not provided".
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.0.trim().is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    // The tags of a list, as `FormData::get_list` splits them.
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let values = self.0
+            .split(VALUE_SEPARATOR)
+            .filter(|value| !value.is_empty())
+            .map(|value| ValueDeserializer(value.to_string()))
+            .collect::<Vec<_>>();
+
+        visitor.visit_seq(SeqDeserializer::new(values.into_iter()))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+        IntoDeserializer::<FormDataError>::into_deserializer(self.0).deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Plan {
+        Free,
+        Pro
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Signup {
+        first_name: String,
+        age: u8,
+        ratio: f64,
+        subscribe: bool,
+        nickname: Option<String>,
+        plan: Plan,
+        interests: Vec<String>
+    }
+
+    fn data(values: &[(&str, &str)]) -> FormData {
+        values.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn to_struct_parses_each_field() {
+        let signup = data(&[
+            ("First Name", "Ada"),
+            ("Age", " 36 "),
+            ("Ratio", "0.5"),
+            ("Subscribe", "yes"),
+            ("Nickname", ""),
+            ("Plan", "Pro"),
+            ("Interests", "maths\nengines")
+        ]).to_struct::<Signup>().unwrap();
+
+        assert_eq!(signup, Signup {
+            first_name: "Ada".into(),
+            age: 36,
+            ratio: 0.5,
+            subscribe: true,
+            nickname: None,
+            plan: Plan::Pro,
+            interests: vec!["maths".into(), "engines".into()]
+        });
+    }
+
+    #[test]
+    fn to_struct_reports_invalid_values() {
+        let error = data(&[("First Name", "Ada"), ("Age", "old")]).to_struct::<Signup>().unwrap_err();
+        assert!(matches!(&error, Error::InvalidData(message) if message.contains("\"old\"")), "{}", error);
+
+        assert!(data(&[("Subscribe", "maybe")]).to_struct::<Signup>().is_err());
+    }
+
+    #[test]
+    fn empty_lists_have_no_tags() {
+        #[derive(Deserialize)]
+        struct Tags {
+            tags: Vec<String>
+        }
+
+        assert!(data(&[("Tags", "")]).to_struct::<Tags>().unwrap().tags.is_empty());
+    }
+
+    #[test]
+    fn from_struct_round_trips() {
+        let signup = Signup {
+            first_name: "Ada".into(),
+            age: 36,
+            ratio: 0.5,
+            subscribe: false,
+            nickname: Some("Countess".into()),
+            plan: Plan::Free,
+            interests: vec!["maths".into(), "engines".into()]
+        };
+
+        let data = FormData::from_struct(&signup).unwrap();
+        assert_eq!(data.get("first_name"), Some("Ada"));
+        assert_eq!(data.get("age"), Some("36"));
+        assert_eq!(data.get_list("interests"), ["maths", "engines"]);
+        assert_eq!(data.to_struct::<Signup>().unwrap(), signup);
+    }
+
+    #[test]
+    fn from_struct_needs_a_struct_or_map() {
+        assert!(matches!(FormData::from_struct(&42), Err(Error::InvalidData(_))));
+    }
+}
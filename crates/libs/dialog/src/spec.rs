@@ -0,0 +1,658 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use crossterm::style::{Color, Colors};
+use serde::Deserialize;
+
+use crate::{
+    borders::{BorderStyle, Borders},
+    colors::{DialogColors, ListColors},
+    controls::{button::Button, checkbox::Checkbox, field::Field, group::GroupBox, label::Label, list::{List, ListItem, ListMode}, Control},
+    dialog::{Anchor, ButtonAlignment, DialogBuilder, DialogResult, Dimension, Placement},
+    error::Error,
+    layout::Layout,
+    utils::Position,
+    validation::Validation
+};
+
+/// A complete dialog described in TOML or JSON.
+///
+/// ```toml
+/// title = " Add Contact "
+/// margin = { x = 4, y = 1 }
+/// borders = { left = "single", right = "single" }
+///
+/// [[controls]]
+/// type = "field"
+/// name = "First Name"
+/// width = 15
+/// validation = ["required", { min_length = 2 }]
+///
+/// [[controls]]
+/// type = "field"
+/// name = "Last Name"
+/// inline = true    # on the same row as the previous control
+///
+/// [[buttons]]
+/// label = "OK"
+/// result = "ok"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DialogSpec {
+    pub title: String,
+    pub borders: BordersSpec,
+    pub margin: MarginSpec,
+    pub colors: ColorsSpec,
+    pub overlay: bool,
+    pub fill: Option<bool>,
+    pub submit_result: Option<String>,
+    pub cancel_result: Option<String>,
+    /// Lays the control rows out in a grid this many columns wide.
+    pub columns: Option<usize>,
+    pub controls: Vec<ControlSpec>,
+    /// Tabs, each with its own controls, used instead of `controls`.
+    pub pages: Vec<PageSpec>,
+    /// "left", "center", "right" or "spread" (the default).
+    pub button_alignment: Option<String>,
+    pub buttons: Vec<ButtonSpec>,
+    /// Columns, or a share of the terminal such as "50%", instead of fitting the content.
+    pub width: Option<DimensionSpec>,
+    pub height: Option<DimensionSpec>,
+    /// "center" (the default), "inline", "absolute" or a corner or edge such as "top-left" or "bottom".
+    pub placement: Option<String>,
+    /// Position of an absolute dialog, or how far an anchored one is moved inwards.
+    pub offset: MarginSpec
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum DimensionSpec {
+    Fixed(usize),
+    Percent(String)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PageSpec {
+    pub title: String,
+    pub columns: Option<usize>,
+    pub controls: Vec<ControlSpec>
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BordersSpec {
+    pub top: Option<String>,
+    pub left: Option<String>,
+    pub right: Option<String>,
+    pub bottom: Option<String>,
+    pub split: Option<String>
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarginSpec {
+    pub x: usize,
+    pub y: usize
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColorSpec {
+    pub fg: String,
+    pub bg: String
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColorsSpec {
+    pub border: Option<ColorSpec>,
+    pub fill: Option<ColorSpec>,
+    pub overlay: Option<ColorSpec>,
+    pub labels: Option<ColorSpec>,
+    pub inputs: Option<ColorSpec>,
+    pub input_indicators: Option<ColorSpec>,
+    pub ghost_text: Option<ColorSpec>,
+    pub buttons: Option<ColorSpec>,
+    pub button_focus: Option<ColorSpec>,
+    pub list_items: Option<ColorSpec>,
+    pub list_selected: Option<ColorSpec>
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ControlSpec {
+    Field {
+        name: String,
+        #[serde(default = "default_width")]
+        width: usize,
+        max: Option<usize>,
+        #[serde(default)]
+        value: String,
+        #[serde(default)]
+        numeric: bool,
+        #[serde(default)]
+        masked: bool,
+        history: Option<String>,
+        #[serde(default)]
+        validation: Vec<ValidationSpec>,
+        #[serde(default)]
+        inline: bool
+    },
+    Checkbox {
+        name: String,
+        #[serde(default)]
+        checked: bool,
+        #[serde(default)]
+        inline: bool
+    },
+    Label {
+        text: String,
+        wrap: Option<usize>,
+        #[serde(default)]
+        inline: bool
+    },
+    List {
+        name: String,
+        #[serde(default)]
+        mode: ListModeSpec,
+        rows: Option<usize>,
+        items: Vec<ListItemSpec>,
+        #[serde(default)]
+        inline: bool
+    },
+    /// A titled frame around its own controls.
+    Group {
+        #[serde(default)]
+        title: String,
+        style: Option<String>,
+        columns: Option<usize>,
+        controls: Vec<ControlSpec>,
+        #[serde(default)]
+        inline: bool
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListModeSpec {
+    #[default]
+    Menu,
+    Check,
+    Radio
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ListItemSpec {
+    pub tag: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub selected: bool
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationSpec {
+    Required,
+    MinLength(usize),
+    MaxLength(usize),
+    Numeric,
+    OneOf(Vec<String>)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ButtonSpec {
+    pub label: String,
+    pub result: String
+}
+
+fn default_width() -> usize {
+    15
+}
+
+impl DialogSpec {
+    /// Loads a spec, choosing the format from the file extension (`.json`, anything else is TOML).
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let contents = fs::read_to_string(&path)?;
+
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json(&contents),
+            _ => Self::from_toml(&contents)
+        }
+    }
+
+    pub fn from_toml(spec: &str) -> Result<Self, Error> {
+        toml::from_str(spec).map_err(|e| Error::InvalidSpec(e.to_string()))
+    }
+
+    pub fn from_json(spec: &str) -> Result<Self, Error> {
+        serde_json::from_str(spec).map_err(|e| Error::InvalidSpec(e.to_string()))
+    }
+
+    pub fn into_builder(self) -> Result<DialogBuilder, Error> {
+        if !self.pages.is_empty() && !self.controls.is_empty() {
+            return Err(Error::InvalidSpec("a spec can have either controls or pages, not both".into()));
+        }
+
+        let mut names = HashSet::new();
+        check_names(&self.controls, &mut names)?;
+        for page in &self.pages {
+            check_names(&page.controls, &mut names)?;
+        }
+
+        let mut builder = DialogBuilder::new(self.title)
+            .set_borders(self.borders.into_borders()?)
+            .set_margin((self.margin.x, self.margin.y).into())
+            .set_colors(self.colors.into_colors()?)
+            .set_overlay(self.overlay)
+            .set_fill(self.fill.unwrap_or(true));
+
+        if let Some(result) = self.submit_result {
+            builder = builder.set_submit_result(parse_result(&result)?);
+        }
+        if let Some(result) = self.cancel_result {
+            builder = builder.set_cancel_result(parse_result(&result)?);
+        }
+        if let Some(alignment) = self.button_alignment {
+            builder = builder.set_button_alignment(parse_button_alignment(&alignment)?);
+        }
+        if let Some(width) = self.width {
+            builder = builder.set_width(width.into_dimension()?);
+        }
+        if let Some(height) = self.height {
+            builder = builder.set_height(height.into_dimension()?);
+        }
+        if let Some(placement) = self.placement {
+            builder = builder.set_placement(parse_placement(&placement, (self.offset.x, self.offset.y).into())?);
+        }
+
+        let mut index = 0;
+        let mut tab_index = 0;
+        if self.pages.is_empty() {
+            let (inner, layout) = add_controls(builder, self.controls, self.columns, &mut index, &mut tab_index)?;
+            builder = inner.set_layout(layout);
+        }
+        for page in self.pages {
+            let (inner, layout) = add_controls(builder, page.controls, page.columns, &mut index, &mut tab_index)?;
+            builder = inner.add_page(page.title, layout);
+        }
+
+        for (index, button) in self.buttons.into_iter().enumerate() {
+            let result = parse_result(&button.result)?;
+            builder = builder.add_control(Control::Button(Button::new(button.label, Some(tab_index + index), result)));
+        }
+
+        Ok(builder)
+    }
+}
+
+fn check_names<'a>(controls: &'a [ControlSpec], names: &mut HashSet<&'a String>) -> Result<(), Error> {
+    for control in controls {
+        match control {
+            ControlSpec::Field { name, .. } | ControlSpec::Checkbox { name, .. } | ControlSpec::List { name, .. } => {
+                if !names.insert(name) {
+                    return Err(Error::DuplicateName(name.clone()));
+                }
+            },
+            ControlSpec::Group { controls, .. } => check_names(controls, names)?,
+            ControlSpec::Label { .. } => {}
+        }
+    }
+
+    Ok(())
+}
+
+// Adds the controls, numbering them in order, and returns the layout of their rows.
+// Inline controls join the row of the control before them.
+fn add_controls(
+    mut builder: DialogBuilder,
+    controls: Vec<ControlSpec>,
+    columns: Option<usize>,
+    index: &mut usize,
+    tab_index: &mut usize
+) -> Result<(DialogBuilder, Layout), Error> {
+    let mut rows: Vec<Vec<usize>> = Vec::new();
+    for control in controls {
+        let this = *index;
+        *index += 1;
+
+        match rows.last_mut() {
+            Some(row) if control.is_inline() => row.push(this),
+            _ => rows.push(vec![this])
+        }
+
+        match control {
+            ControlSpec::Group { title, style, columns, controls, .. } => {
+                // Groups default to a single line frame rather than the dialog's double border.
+                let style = match style {
+                    Some(style) => parse_border_style(Some(style))?,
+                    None => BorderStyle::Single
+                };
+                let (inner, layout) = add_controls(builder, controls, columns, index, tab_index)?;
+                builder = inner.add_control(Control::Group(GroupBox::new(title, layout, this).set_style(style)));
+            },
+            control => {
+                // Labels can't take focus, so tab indices only count the other controls.
+                let focusable = !matches!(control, ControlSpec::Label { .. });
+                builder = builder.add_control(control.into_control(*tab_index, this)?);
+                *tab_index += focusable as usize;
+            }
+        }
+    }
+
+    let rows = rows.into_iter().map(|row| match row[..] {
+        [index] => Layout::control(index),
+        _ => Layout::horizontal(row.into_iter().map(Layout::control))
+    });
+
+    match columns {
+        Some(0) => Err(Error::InvalidSpec("columns must be at least 1".into())),
+        Some(columns) => Ok((builder, Layout::grid(columns, rows))),
+        None => Ok((builder, Layout::vertical(rows)))
+    }
+}
+
+impl ControlSpec {
+    fn is_inline(&self) -> bool {
+        match self {
+            ControlSpec::Field { inline, .. }
+            | ControlSpec::Checkbox { inline, .. }
+            | ControlSpec::Label { inline, .. }
+            | ControlSpec::List { inline, .. }
+            | ControlSpec::Group { inline, .. } => *inline
+        }
+    }
+
+    fn into_control(self, tab_index: usize, index: usize) -> Result<Control, Error> {
+        match self {
+            ControlSpec::Field { name, width, max, value, numeric, masked, history, validation, .. } => {
+                if width == 0 {
+                    return Err(Error::InvalidSpec(format!("field {:?} must have a width of at least 1", name)));
+                }
+
+                let mut field = Field::new(name, width, max.unwrap_or(width), Some(tab_index), index)
+                    .set_numeric(numeric)
+                    .set_masked(masked)
+                    .set_initial_value(value);
+
+                if let Some(key) = history {
+                    field = field.set_history(key);
+                }
+
+                Ok(Control::TextField(validation
+                    .into_iter()
+                    .map(ValidationSpec::into_validation)
+                    .fold(field, Field::add_validation)))
+            },
+            ControlSpec::Checkbox { name, checked, .. } => Ok(Control::Checkbox(Checkbox::new(name, checked, Some(tab_index), index))),
+            ControlSpec::Label { text, wrap, .. } => Ok(Control::Label(match wrap {
+                Some(width) => Label::new(text, index).set_wrap(width),
+                None => Label::new(text, index)
+            })),
+            ControlSpec::List { name, mode, rows, items, .. } => {
+                if items.is_empty() {
+                    return Err(Error::InvalidSpec(format!("list {:?} has no items", name)));
+                }
+
+                let mode = match mode {
+                    ListModeSpec::Menu => ListMode::Menu,
+                    ListModeSpec::Check => ListMode::Check,
+                    ListModeSpec::Radio => ListMode::Radio
+                };
+                let rows = rows.unwrap_or(items.len());
+                let items = items.into_iter().map(|i| ListItem::new(i.tag, i.description, i.selected)).collect();
+                Ok(Control::List(List::new(name, items, rows, mode, Some(tab_index), index)))
+            },
+            ControlSpec::Group { .. } => unreachable!("groups are added by add_controls")
+        }
+    }
+}
+
+impl ValidationSpec {
+    fn into_validation(self) -> Validation {
+        match self {
+            ValidationSpec::Required => Validation::Required,
+            ValidationSpec::MinLength(len) => Validation::MinLength(len),
+            ValidationSpec::MaxLength(len) => Validation::MaxLength(len),
+            ValidationSpec::Numeric => Validation::Numeric,
+            ValidationSpec::OneOf(values) => Validation::OneOf(values)
+        }
+    }
+}
+
+impl BordersSpec {
+    fn into_borders(self) -> Result<Borders, Error> {
+        Ok(Borders::new(
+            parse_border_style(self.top)?,
+            parse_border_style(self.left)?,
+            parse_border_style(self.right)?,
+            parse_border_style(self.bottom)?,
+            parse_border_style(self.split)?
+        ))
+    }
+}
+
+impl ColorsSpec {
+    fn into_colors(self) -> Result<DialogColors, Error> {
+        let mut colors = DialogColors::default();
+
+        if let Some(c) = self.border { colors.border = c.into_colors()?; }
+        if let Some(c) = self.fill { colors.fill = c.into_colors()?; }
+        if let Some(c) = self.overlay { colors.overlay = c.into_colors()?; }
+        if let Some(c) = self.labels { colors.fields.label = c.into_colors()?; }
+        if let Some(c) = self.inputs { colors.fields.input.input = c.into_colors()?; }
+        if let Some(c) = self.input_indicators { colors.fields.input.focus = c.into_colors()?; }
+        if let Some(c) = self.ghost_text { colors.fields.input.ghost = c.into_colors()?; }
+        if let Some(c) = self.buttons { colors.buttons.button = c.into_colors()?; }
+        if let Some(c) = self.button_focus { colors.buttons.focus = c.into_colors()?; }
+
+        let mut lists = ListColors::default();
+        if let Some(c) = self.list_items { lists.item = c.into_colors()?; }
+        if let Some(c) = self.list_selected { lists.selected = c.into_colors()?; }
+
+        Ok(colors.set_suggestions(lists.clone()).set_lists(lists))
+    }
+}
+
+impl ColorSpec {
+    fn into_colors(self) -> Result<Colors, Error> {
+        Ok(Colors::new(parse_color(&self.fg)?, parse_color(&self.bg)?))
+    }
+}
+
+fn parse_color(color: &str) -> Result<Color, Error> {
+    Color::try_from(color).map_err(|_| Error::InvalidSpec(format!("unknown color {:?}", color)))
+}
+
+fn parse_border_style(style: Option<String>) -> Result<BorderStyle, Error> {
+    match style.as_deref() {
+        None => Ok(BorderStyle::default()),
+        Some("single") => Ok(BorderStyle::Single),
+        Some("double") => Ok(BorderStyle::Double),
+        Some(other) => Err(Error::InvalidSpec(format!("unknown border style {:?}, expected \"single\" or \"double\"", other)))
+    }
+}
+
+fn parse_button_alignment(alignment: &str) -> Result<ButtonAlignment, Error> {
+    match alignment {
+        "left" => Ok(ButtonAlignment::Left),
+        "center" => Ok(ButtonAlignment::Center),
+        "right" => Ok(ButtonAlignment::Right),
+        "spread" => Ok(ButtonAlignment::Spread),
+        other => Err(Error::InvalidSpec(format!("unknown button alignment {:?}, expected \"left\", \"center\", \"right\" or \"spread\"", other)))
+    }
+}
+
+impl DimensionSpec {
+    fn into_dimension(self) -> Result<Dimension, Error> {
+        match self {
+            DimensionSpec::Fixed(length) => Ok(Dimension::Fixed(length)),
+            DimensionSpec::Percent(percent) => percent
+                .strip_suffix('%')
+                .and_then(|percent| percent.trim().parse().ok())
+                .map(Dimension::Percent)
+                .ok_or_else(|| Error::InvalidSpec(format!("invalid size {:?}, expected a number or a percentage", percent)))
+        }
+    }
+}
+
+fn parse_placement(placement: &str, offset: Position) -> Result<Placement, Error> {
+    let anchor = match placement {
+        "center" => return Ok(Placement::Center),
+        "inline" => return Ok(Placement::Inline),
+        "absolute" => return Ok(Placement::Absolute(offset)),
+        "top-left" => Anchor::TopLeft,
+        "top" => Anchor::Top,
+        "top-right" => Anchor::TopRight,
+        "left" => Anchor::Left,
+        "right" => Anchor::Right,
+        "bottom-left" => Anchor::BottomLeft,
+        "bottom" => Anchor::Bottom,
+        "bottom-right" => Anchor::BottomRight,
+        other => return Err(Error::InvalidSpec(format!("unknown placement {:?}", other)))
+    };
+
+    Ok(Placement::Anchored(anchor, offset))
+}
+
+fn parse_result(result: &str) -> Result<DialogResult, Error> {
+    result.parse().map_err(Error::InvalidSpec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid(spec: DialogSpec) -> String {
+        match spec.into_builder() {
+            Err(Error::InvalidSpec(message)) => message,
+            other => panic!("expected an invalid spec, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn toml_builds_a_dialog() {
+        let spec = DialogSpec::from_toml(r#"
+            title = " Add Contact "
+            width = "50%"
+            placement = "bottom-right"
+
+            [[controls]]
+            type = "field"
+            name = "First Name"
+            value = "Ada"
+
+            [[controls]]
+            type = "checkbox"
+            name = "Active"
+            checked = true
+            inline = true
+
+            [[controls]]
+            type = "list"
+            name = "Role"
+            mode = "radio"
+            items = [{ tag = "admin" }, { tag = "user", selected = true }]
+
+            [[buttons]]
+            label = "OK"
+            result = "ok"
+        "#).unwrap();
+
+        assert!(matches!(spec.width, Some(DimensionSpec::Percent(ref percent)) if percent == "50%"));
+        let data = spec.into_builder().unwrap().build().unwrap().get_data();
+        assert_eq!(data.get("First Name"), Some("Ada"));
+        assert_eq!(data.get("Active"), Some("true"));
+        assert_eq!(data.get("Role"), Some("user"));
+    }
+
+    #[test]
+    fn json_builds_pages() {
+        let spec = DialogSpec::from_json(r#"{
+            "title": "Settings",
+            "width": 40,
+            "pages": [
+                { "title": "General", "controls": [{ "type": "field", "name": "Name", "width": 10 }] },
+                { "title": "Advanced", "columns": 2, "controls": [
+                    { "type": "group", "title": "Flags", "controls": [{ "type": "checkbox", "name": "Verbose" }] }
+                ] }
+            ]
+        }"#).unwrap();
+
+        assert_eq!(spec.pages.len(), 2);
+        let data = spec.into_builder().unwrap().build().unwrap().get_data();
+        assert_eq!(data.get("Name"), Some(""));
+        assert_eq!(data.get("Verbose"), Some("false"));
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(matches!(DialogSpec::from_toml("titel = \"typo\""), Err(Error::InvalidSpec(_))));
+        assert!(matches!(DialogSpec::from_json(r#"{ "controls": [{ "type": "slider", "name": "x" }] }"#), Err(Error::InvalidSpec(_))));
+    }
+
+    #[test]
+    fn zero_columns_are_rejected() {
+        let spec = DialogSpec::from_toml("columns = 0\n[[controls]]\ntype = \"checkbox\"\nname = \"a\"").unwrap();
+        assert_eq!(invalid(spec), "columns must be at least 1");
+
+        let spec = DialogSpec::from_toml("[[pages]]\ntitle = \"p\"\ncolumns = 0\ncontrols = []").unwrap();
+        assert_eq!(invalid(spec), "columns must be at least 1");
+    }
+
+    #[test]
+    fn empty_lists_are_rejected() {
+        let spec = DialogSpec::from_toml("[[controls]]\ntype = \"list\"\nname = \"Role\"\nitems = []").unwrap();
+        assert_eq!(invalid(spec), "list \"Role\" has no items");
+    }
+
+    #[test]
+    fn zero_width_fields_are_rejected() {
+        let spec = DialogSpec::from_toml("[[controls]]\ntype = \"field\"\nname = \"Name\"\nwidth = 0").unwrap();
+        assert_eq!(invalid(spec), "field \"Name\" must have a width of at least 1");
+    }
+
+    #[test]
+    fn pages_and_controls_are_exclusive() {
+        let spec = DialogSpec::from_toml(r#"
+            [[controls]]
+            type = "checkbox"
+            name = "a"
+
+            [[pages]]
+            title = "p"
+            controls = []
+        "#).unwrap();
+        assert_eq!(invalid(spec), "a spec can have either controls or pages, not both");
+    }
+
+    #[test]
+    fn names_are_unique_across_pages_and_groups() {
+        let spec = DialogSpec::from_toml(r#"
+            [[pages]]
+            title = "One"
+            controls = [{ type = "checkbox", name = "a" }]
+
+            [[pages]]
+            title = "Two"
+            controls = [{ type = "group", controls = [{ type = "field", name = "a" }] }]
+        "#).unwrap();
+        assert!(matches!(spec.into_builder(), Err(Error::DuplicateName(name)) if name == "a"));
+    }
+
+    #[test]
+    fn bad_values_are_rejected() {
+        for (spec, message) in [
+            ("width = \"half\"", "invalid size \"half\", expected a number or a percentage"),
+            ("placement = \"middle\"", "unknown placement \"middle\""),
+            ("button_alignment = \"justify\"", "unknown button alignment \"justify\", expected \"left\", \"center\", \"right\" or \"spread\""),
+            ("borders = { left = \"dotted\" }", "unknown border style \"dotted\", expected \"single\" or \"double\""),
+            ("colors = { border = { fg = \"mauve\", bg = \"black\" } }", "unknown color \"mauve\"")
+        ] {
+            assert_eq!(invalid(DialogSpec::from_toml(spec).unwrap()), message, "{}", spec);
+        }
+    }
+}
@@ -32,4 +32,14 @@ impl From<(u16, u16)> for Size {
         Self { width: value.0 as usize, height: value.1 as usize }
     }
 }
-// endregion: -- Size
\ No newline at end of file
+// endregion: -- Size
+
+/// Normalises a control name into an identifier-like key, e.g. "First Name" -> "first_name".
+pub(crate) fn field_key(name: &str) -> String {
+    name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
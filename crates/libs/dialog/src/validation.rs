@@ -0,0 +1,47 @@
+use std::{fmt::Debug, rc::Rc};
+
+type Validator = Rc<dyn Fn(&str) -> Result<(), String>>;
+
+#[derive(Clone)]
+pub enum Validation {
+    Required,
+    MinLength(usize),
+    MaxLength(usize),
+    Numeric,
+    OneOf(Vec<String>),
+    Custom(Validator)
+}
+
+impl Debug for Validation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Validation::Required => write!(f, "Required"),
+            Validation::MinLength(len) => write!(f, "MinLength({})", len),
+            Validation::MaxLength(len) => write!(f, "MaxLength({})", len),
+            Validation::Numeric => write!(f, "Numeric"),
+            Validation::OneOf(values) => write!(f, "OneOf({:?})", values),
+            Validation::Custom(_) => write!(f, "Custom")
+        }
+    }
+}
+
+impl Validation {
+    /// A validator returning the message to show when the value is rejected.
+    pub fn custom(validator: impl Fn(&str) -> Result<(), String> + 'static) -> Self {
+        Self::Custom(Rc::new(validator))
+    }
+
+    pub(crate) fn validate(&self, name: &str, value: &str) -> Result<(), String> {
+        let len = value.chars().count();
+
+        match self {
+            Validation::Required if value.trim().is_empty() => Err(format!("{} is required", name)),
+            Validation::MinLength(min) if len > 0 && len < *min => Err(format!("{} needs at least {} characters", name, min)),
+            Validation::MaxLength(max) if len > *max => Err(format!("{} allows at most {} characters", name, max)),
+            Validation::Numeric if !value.trim().is_empty() && value.trim().parse::<f64>().is_err() => Err(format!("{} must be a number", name)),
+            Validation::OneOf(values) if !value.is_empty() && !values.iter().any(|v| v == value) => Err(format!("{} must be one of {}", name, values.join(", "))),
+            Validation::Custom(validator) => validator(value),
+            _ => Ok(())
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use std::fmt::Debug;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use crate::{
+    controls::{button::Button, Control},
+    dialog::{Dialog, DialogBuilder, DialogResult, DialogReturnValue, FormData},
+    error::Error
+};
+
+const BACK_LABEL: &str = "< Back";
+const NEXT_LABEL: &str = "Next >";
+const FINISH_LABEL: &str = "Finish";
+const CANCEL_LABEL: &str = "Cancel";
+
+type Condition = Box<dyn Fn(&FormData) -> bool>;
+
+struct WizardPage {
+    dialog: Dialog,
+    condition: Option<Condition>
+}
+
+/// A sequence of dialogs filled in one after another, e.g. an installer.
+///
+/// Each page gets Back, Next and Cancel buttons, with Next becoming Finish on the last page, and
+/// a "Step 2 of 4" indicator on its split line. A page is validated before moving on from it and
+/// pages added with `add_page_if` are skipped unless their condition holds for the answers so far.
+/// Finishing returns `DialogResult::Ok`, cancelling returns the page's cancel result.
+pub struct Wizard {
+    pages: Vec<WizardPage>,
+    current: usize,
+    visited: Vec<usize>
+}
+
+impl Debug for Wizard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Wizard")
+            .field("pages", &self.pages.len())
+            .field("current", &self.current)
+            .field("visited", &self.visited)
+            .finish()
+    }
+}
+
+impl Default for Wizard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Wizard {
+    pub fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            current: 0,
+            visited: Vec::new()
+        }
+    }
+
+    /// Adds a page. The wizard adds the buttons, so the builder shouldn't have any of its own.
+    pub fn add_page(mut self, builder: DialogBuilder) -> Result<Self, Error> {
+        self.pages.push(WizardPage { dialog: build_page(builder)?, condition: None });
+
+        Ok(self)
+    }
+
+    /// Adds a page that is only shown when `condition` holds for the values entered so far.
+    pub fn add_page_if(mut self, builder: DialogBuilder, condition: impl Fn(&FormData) -> bool + 'static) -> Result<Self, Error> {
+        self.pages.push(WizardPage { dialog: build_page(builder)?, condition: Some(Box::new(condition)) });
+
+        Ok(self)
+    }
+
+    /// Values from the pages on the way to the current one, in page order.
+    pub fn get_data(&self) -> FormData {
+        let mut pages = self.visited.clone();
+        pages.push(self.current);
+        pages.sort();
+
+        pages
+            .into_iter()
+            .filter_map(|page| self.pages.get(page))
+            .flat_map(|page| page.dialog.get_data())
+            .collect()
+    }
+
+    pub fn show(&mut self) -> Result<(), Error> {
+        // Pages share the size of the largest, so switching doesn't leave parts of the previous one behind.
+        let (width, height) = self.pages
+            .iter()
+            .map(|page| page.dialog.get_min_size())
+            .fold((0, 0), |size, page| (size.0.max(page.0), size.1.max(page.1)));
+        self.pages.iter_mut().for_each(|page| page.dialog.set_min_size(width, height));
+
+        if !self.applies(self.current, &self.get_data()) {
+            match self.next_page() {
+                Some(page) => self.current = page,
+                None => return Ok(())
+            }
+        }
+
+        self.show_page(self.current)
+    }
+
+    pub fn handle_input(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<DialogReturnValue, Error> {
+        let Some(page) = self.pages.get_mut(self.current) else {
+            return Ok(DialogReturnValue { should_quit: true, dialog_result: Some(DialogResult::Cancel), ..Default::default() });
+        };
+
+        let result = page.dialog.handle_input(code, modifiers)?;
+        if !result.should_quit {
+            return Ok(result);
+        }
+
+        match result.dialog_result {
+            Some(DialogResult::Next) => if self.advance() {
+                self.show_page(self.current)?;
+                Ok(DialogReturnValue::default())
+            } else {
+                Ok(DialogReturnValue { should_quit: true, dialog_result: Some(DialogResult::Ok), ..Default::default() })
+            },
+            Some(DialogResult::Back) => {
+                if self.retreat() {
+                    self.show_page(self.current)?;
+                }
+                Ok(DialogReturnValue::default())
+            },
+            _ => Ok(result)
+        }
+    }
+
+    fn applies(&self, page: usize, data: &FormData) -> bool {
+        self.pages
+            .get(page)
+            .is_some_and(|page| page.condition.as_ref().is_none_or(|condition| condition(data)))
+    }
+
+    fn next_page(&self) -> Option<usize> {
+        let data = self.get_data();
+        (self.current + 1..self.pages.len()).find(|page| self.applies(*page, &data))
+    }
+
+    // Moves to the next page that applies, returning false on the last one.
+    fn advance(&mut self) -> bool {
+        match self.next_page() {
+            Some(page) => {
+                self.visited.push(self.current);
+                self.current = page;
+                true
+            },
+            None => false
+        }
+    }
+
+    // Moves back to the page shown before the current one, so skipped pages stay skipped.
+    fn retreat(&mut self) -> bool {
+        match self.visited.pop() {
+            Some(page) => {
+                self.current = page;
+                true
+            },
+            None => false
+        }
+    }
+
+    // The current step and the number of steps, counting later pages with the answers so far,
+    // so the total can change as the user goes.
+    fn step(&self) -> (usize, usize) {
+        let data = self.get_data();
+        let step = self.visited.len() + 1;
+        let total = step + (self.current + 1..self.pages.len()).filter(|page| self.applies(*page, &data)).count();
+
+        (step, total)
+    }
+
+    fn show_page(&mut self, index: usize) -> Result<(), Error> {
+        self.current = index;
+        let (step, total) = self.step();
+
+        let dialog = &mut self.pages[index].dialog;
+        dialog.set_button_label(&DialogResult::Next, if step == total { FINISH_LABEL } else { NEXT_LABEL });
+        dialog.set_status(Some(format!("Step {} of {}", step, total)))?;
+        dialog.show()
+    }
+}
+
+fn build_page(builder: DialogBuilder) -> Result<Dialog, Error> {
+    let tab_index = builder.next_tab_index();
+
+    builder
+        .set_submit_result(DialogResult::Next)
+        .add_control(Control::Button(Button::new(BACK_LABEL, Some(tab_index), DialogResult::Back)))
+        .add_control(Control::Button(Button::new(NEXT_LABEL, Some(tab_index + 1), DialogResult::Next)))
+        .add_control(Control::Button(Button::new(CANCEL_LABEL, Some(tab_index + 2), DialogResult::Cancel)))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controls::checkbox::Checkbox;
+
+    use super::*;
+
+    fn page(name: &str) -> DialogBuilder {
+        DialogBuilder::new(name).add_control(Control::Checkbox(Checkbox::new(name, false, Some(0), 0)))
+    }
+
+    // Four pages where the third is only shown when "Advanced" is ticked on the first.
+    fn wizard() -> Wizard {
+        Wizard::new()
+            .add_page(page("Advanced")).unwrap()
+            .add_page(page("Basics")).unwrap()
+            .add_page_if(page("Tuning"), |data| data.get("Advanced") == Some("true")).unwrap()
+            .add_page(page("Summary")).unwrap()
+    }
+
+    fn tick_advanced(wizard: &mut Wizard) {
+        wizard.pages[0].dialog.set_value("Advanced", "true").unwrap();
+    }
+
+    #[test]
+    fn next_page_skips_pages_whose_condition_fails() {
+        let mut wizard = wizard();
+        assert!(wizard.advance());
+        assert_eq!(wizard.next_page(), Some(3));
+
+        tick_advanced(&mut wizard);
+        assert_eq!(wizard.next_page(), Some(2));
+    }
+
+    #[test]
+    fn step_counts_only_pages_that_apply() {
+        let mut wizard = wizard();
+        assert_eq!(wizard.step(), (1, 3));
+
+        tick_advanced(&mut wizard);
+        assert_eq!(wizard.step(), (1, 4));
+
+        assert!(wizard.advance());
+        assert!(wizard.advance());
+        assert_eq!((wizard.current, wizard.step()), (2, (3, 4)));
+    }
+
+    #[test]
+    fn back_returns_past_skipped_pages() {
+        let mut wizard = wizard();
+        assert!(wizard.advance());
+        assert!(wizard.advance());
+        assert_eq!((wizard.current, wizard.step()), (3, (3, 3)));
+        assert!(!wizard.advance());
+
+        assert!(wizard.retreat());
+        assert_eq!(wizard.current, 1);
+        assert!(wizard.retreat());
+        assert_eq!(wizard.current, 0);
+        assert!(!wizard.retreat());
+    }
+
+    #[test]
+    fn data_only_includes_visited_pages() {
+        let mut wizard = wizard();
+        assert!(wizard.advance());
+        assert!(wizard.advance());
+
+        let names = wizard.get_data().iter().map(|(name, _)| name.to_string()).collect::<Vec<_>>();
+        assert_eq!(names, ["Advanced", "Basics", "Summary"]);
+    }
+}
@@ -0,0 +1,260 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, GenericArgument, LitInt, LitStr, PathArguments, Type};
+
+const DEFAULT_WIDTH: usize = 15;
+
+const NUMERIC_TYPES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize",
+    "u8", "u16", "u32", "u64", "u128", "usize",
+    "f32", "f64"
+];
+
+#[derive(Default)]
+struct FieldAttrs {
+    label: Option<String>,
+    width: Option<usize>,
+    max: Option<usize>,
+    history: Option<String>,
+    completion: Option<syn::Path>,
+    inline: bool,
+    skip: bool
+}
+
+enum Kind {
+    Text,
+    Numeric,
+    Checkbox
+}
+
+/// Generates a `dialog::form::DialogForm` implementation for a struct with named fields.
+///
+/// `bool` fields become checkboxes, numeric fields only accept numbers and everything else is a
+/// text field. Use `#[dialog(title = "...", columns = 2)]` on the struct and
+/// `#[dialog(label = "...", width = 15, max = 40, history = "key", completion = path::to::fn)]`,
+/// `#[dialog(inline)]` (same row as the previous field) or `#[dialog(skip)]` on fields.
+#[proc_macro_derive(Dialog, attributes(dialog))]
+pub fn derive_dialog(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut title = String::new();
+    let mut columns = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("dialog")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("title") {
+                title = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else if meta.path.is_ident("columns") {
+                let value = meta.value()?.parse::<LitInt>()?;
+                match value.base10_parse::<usize>()? {
+                    0 => Err(syn::Error::new_spanned(value, "columns must be at least 1")),
+                    n => { columns = Some(n); Ok(()) }
+                }
+            } else {
+                Err(meta.error("expected `title` or `columns`"))
+            }
+        })?;
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(name, "Dialog can only be derived for structs with named fields"))
+        },
+        _ => return Err(syn::Error::new_spanned(name, "Dialog can only be derived for structs"))
+    };
+
+    let mut controls = Vec::new();
+    let mut values = Vec::new();
+    let mut rows: Vec<Vec<usize>> = Vec::new();
+
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let attrs = parse_field_attrs(field)?;
+
+        if attrs.skip {
+            values.push(quote! { #ident: ::core::default::Default::default() });
+            continue;
+        }
+
+        let index = controls.len();
+        match rows.last_mut() {
+            Some(row) if attrs.inline => row.push(index),
+            _ => rows.push(vec![index])
+        }
+
+        let label = attrs.label.unwrap_or_else(|| label_from_ident(&ident.to_string()));
+        let (optional, inner) = match option_inner(&field.ty) {
+            Some(inner) => (true, inner),
+            None => (false, &field.ty)
+        };
+
+        let kind = match type_name(inner).as_deref() {
+            Some("bool") => Kind::Checkbox,
+            Some(ty) if NUMERIC_TYPES.contains(&ty) => Kind::Numeric,
+            _ => Kind::Text
+        };
+
+        let width = attrs.width.unwrap_or(DEFAULT_WIDTH);
+        let max = attrs.max.unwrap_or(width);
+        let history = attrs.history.map(|key| quote! { .set_history(#key) });
+        let completion = attrs.completion.map(|provider| quote! {
+            .set_completion(#provider, ::dialog::completion::CompletionStyle::Inline)
+        });
+
+        controls.push(match kind {
+            Kind::Checkbox => quote! {
+                ::dialog::controls::Control::Checkbox(
+                    ::dialog::controls::checkbox::Checkbox::new(#label, false, Some(#index), #index))
+            },
+            Kind::Numeric | Kind::Text => {
+                let numeric = matches!(kind, Kind::Numeric);
+                quote! {
+                    ::dialog::controls::Control::TextField(
+                        ::dialog::controls::field::Field::new(#label, #width, #max, Some(#index), #index)
+                            .set_numeric(#numeric)
+                            #history
+                            #completion)
+                }
+            }
+        });
+
+        let parse = quote! {
+            value.trim().parse::<#inner>().map_err(|e| ::dialog::form::FormError::Invalid {
+                field: #label.to_string(),
+                message: e.to_string()
+            })?
+        };
+
+        values.push(match (optional, type_name(inner).as_deref()) {
+            (true, _) => quote! {
+                #ident: match data.get(#label) {
+                    Some(value) if !value.trim().is_empty() => Some(#parse),
+                    _ => None
+                }
+            },
+            (false, Some("String")) => quote! {
+                #ident: data.get(#label)
+                    .ok_or_else(|| ::dialog::form::FormError::Missing(#label.to_string()))?
+                    .to_string()
+            },
+            (false, _) => quote! {
+                #ident: {
+                    let value = data.get(#label)
+                        .ok_or_else(|| ::dialog::form::FormError::Missing(#label.to_string()))?;
+                    #parse
+                }
+            }
+        });
+    }
+
+    let field_count = controls.len();
+
+    let layout = (columns.is_some() || rows.len() < field_count).then(|| {
+        let rows = rows.iter().map(|row| match row[..] {
+            [index] => quote! { ::dialog::layout::Layout::control(#index) },
+            _ => quote! { ::dialog::layout::Layout::horizontal([#(::dialog::layout::Layout::control(#row)),*]) }
+        });
+        let layout = match columns {
+            Some(columns) => quote! { ::dialog::layout::Layout::grid(#columns, [#(#rows),*]) },
+            None => quote! { ::dialog::layout::Layout::vertical([#(#rows),*]) }
+        };
+        quote! { .set_layout(#layout) }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::dialog::form::DialogForm for #name #ty_generics #where_clause {
+            const FIELD_COUNT: usize = #field_count;
+
+            fn dialog_builder() -> ::dialog::dialog::DialogBuilder {
+                ::dialog::dialog::DialogBuilder::new(#title)
+                    #(.add_control(#controls))*
+                    #layout
+            }
+
+            fn from_form_data(data: &::dialog::dialog::FormData) -> ::core::result::Result<Self, ::dialog::form::FormError> {
+                Ok(Self {
+                    #(#values),*
+                })
+            }
+        }
+    })
+}
+
+fn parse_field_attrs(field: &syn::Field) -> syn::Result<FieldAttrs> {
+    let mut attrs = FieldAttrs::default();
+
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("dialog")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("label") {
+                attrs.label = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("width") {
+                attrs.width = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+            } else if meta.path.is_ident("max") {
+                attrs.max = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+            } else if meta.path.is_ident("history") {
+                attrs.history = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("completion") {
+                attrs.completion = Some(meta.value()?.parse::<syn::Path>()?);
+            } else if meta.path.is_ident("inline") {
+                attrs.inline = true;
+            } else if meta.path.is_ident("skip") {
+                attrs.skip = true;
+            } else {
+                return Err(meta.error("expected `label`, `width`, `max`, `history`, `completion`, `inline` or `skip`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(attrs)
+}
+
+// "first_name" -> "First Name"
+fn label_from_ident(ident: &str) -> String {
+    ident
+        .trim_start_matches("r#")
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map(|c| c.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn type_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None
+    }
+}
+
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None
+        }),
+        _ => None
+    }
+}